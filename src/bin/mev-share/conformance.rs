@@ -0,0 +1,308 @@
+//! The `conformance` command, running the spec test vectors of `tests/tests.json` against a
+//! matchmaker endpoint or the local checks of this crate
+//!
+//! Against an endpoint every bundle is simulated with mev_simBundle, or sent with mev_sendBundle
+//! with `--send`, and the outcome is compared with the vector's `shouldFail`. Only a JSON-RPC error
+//! or a failed simulation counts as a rejected bundle, requests that fail otherwise, e.g. because
+//! the endpoint is unreachable, fail the vector. mev_simBundle only reports the total refundable
+//! value, so vectors with extracted refunds are skipped once the outcome matches.
+//!
+//! `--send` submits the spec bundles for real, so it is refused for targets other than localhost
+//! unless `--allow-remote` is given.
+//!
+//! The local target runs the checks that need no EVM: bundle validation, transaction decoding,
+//! sender nonces against the genesis state and the recipients and percentages of the extracted
+//! refunds. Vectors that are only decidable by executing the bundle, e.g. reverting transactions,
+//! are skipped, as are refund values.
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use ethers::{
+    signers::LocalWallet,
+    types::{Address, U256},
+};
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::MatchmakerConfig,
+    fixtures::{SpecFixture, SpecTest},
+    types::{Bundle, BundleTx, SimBundleOverrides},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    net::IpAddr,
+    path::PathBuf,
+};
+
+#[derive(Debug, Args)]
+pub struct ConformanceArgs {
+    /// Spec test vectors
+    #[arg(long, default_value = "tests/tests.json")]
+    fixtures: PathBuf,
+    /// `local` for the local checks, or the URL of the matchmaker API to test
+    #[arg(long, default_value = "local")]
+    target: String,
+    /// Send bundles with mev_sendBundle instead of simulating them with mev_simBundle
+    #[arg(long)]
+    send: bool,
+    /// Allow `--send` to a target other than localhost
+    #[arg(long, requires = "send")]
+    allow_remote: bool,
+}
+
+/// How an endpoint answered a test vector
+enum Answer {
+    /// The bundle was accepted, with the refundable value if it was simulated
+    Accepted(Option<U256>),
+    /// The endpoint rejected the bundle
+    Rejected(String),
+    /// The request failed without an answer about the bundle
+    Failed(anyhow::Error),
+}
+
+impl From<anyhow::Error> for Answer {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast_ref::<jsonrpsee::core::Error>() {
+            Some(jsonrpsee::core::Error::Call(_)) => Answer::Rejected(err.to_string()),
+            _ => Answer::Failed(err),
+        }
+    }
+}
+
+/// Result of a single test vector
+enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "PASS"),
+            Outcome::Fail(reason) => write!(f, "FAIL  {reason}"),
+            Outcome::Skip(reason) => write!(f, "SKIP  {reason}"),
+        }
+    }
+}
+
+/// Runs all vectors and prints a report. Returns false if any vector failed.
+pub async fn run(args: ConformanceArgs) -> Result<bool> {
+    let fixture = SpecFixture::from_file(&args.fixtures)?;
+
+    let client = match args.target.as_str() {
+        "local" => None,
+        url => {
+            if args.send && !args.allow_remote && !is_local(url)? {
+                bail!("refusing to send the spec bundles to {url}, pass --allow-remote to send them to a target other than localhost");
+            }
+            Some(endpoint_client(url)?)
+        }
+    };
+
+    let width = fixture.tests.iter().map(|test| test.name.len()).max();
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    for test in &fixture.tests {
+        let outcome = match &client {
+            Some(client) => check_endpoint(client, test, args.send).await,
+            None => check_local(&fixture, test),
+        };
+        match outcome {
+            Outcome::Pass => passed += 1,
+            Outcome::Fail(_) => failed += 1,
+            Outcome::Skip(_) => skipped += 1,
+        }
+        println!(
+            "{:<width$}  {outcome}",
+            test.name,
+            width = width.unwrap_or_default()
+        );
+    }
+    println!("\n{passed} passed, {failed} failed, {skipped} skipped");
+
+    Ok(failed == 0)
+}
+
+/// Whether `url` points at this machine
+fn is_local(url: &str) -> Result<bool> {
+    let uri: http::Uri = url
+        .parse()
+        .with_context(|| format!("invalid conformance target {url}"))?;
+    let host = uri.host().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    Ok(host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback()))
+}
+
+fn endpoint_client(url: &str) -> Result<MatchmakerClient<LocalWallet>> {
+    let config = MatchmakerConfig {
+        network: "conformance".to_string(),
+        chain_id: Some(1),
+        stream_url: Some(url.to_string()),
+        api_url: Some(url.to_string()),
+        auth_key: Some(crate::random_auth_key()),
+        ..Default::default()
+    };
+    let client = MatchmakerClient::from_config(&config)
+        .with_context(|| format!("invalid conformance target {url}"))?;
+
+    // the endpoint is under test, so bundles are not rejected client-side
+    Ok(client.with_bundle_validation(false))
+}
+
+async fn check_endpoint(
+    client: &MatchmakerClient<LocalWallet>,
+    test: &SpecTest,
+    send: bool,
+) -> Outcome {
+    let answer = if send {
+        match client.send_bundle(&test.bundle).await {
+            Ok(_) => Answer::Accepted(None),
+            Err(err) => err.into(),
+        }
+    } else {
+        match client
+            .sim_bundle(&test.bundle, &SimBundleOverrides::default())
+            .await
+        {
+            Ok(response) if response.success => Answer::Accepted(Some(response.refundable_value)),
+            Ok(response) => Answer::Rejected(
+                response
+                    .error
+                    .unwrap_or_else(|| "simulation failed".to_string()),
+            ),
+            Err(err) => err.into(),
+        }
+    };
+
+    match (test.should_fail, answer) {
+        (_, Answer::Failed(err)) => Outcome::Fail(format!("request failed: {err}")),
+        (true, Answer::Accepted(_)) => Outcome::Fail("expected to fail, but succeeded".to_string()),
+        (true, Answer::Rejected(_)) => Outcome::Pass,
+        (false, Answer::Rejected(err)) => {
+            Outcome::Fail(format!("expected to succeed, but failed: {err}"))
+        }
+        (false, Answer::Accepted(refundable_value)) if !test.extracted_refunds.is_empty() => {
+            match refundable_value {
+                Some(value) => Outcome::Skip(format!(
+                    "refunds are not reported by mev_simBundle, only the refundable value {value}"
+                )),
+                None => Outcome::Skip("refunds are not reported by mev_sendBundle".to_string()),
+            }
+        }
+        (false, Answer::Accepted(_)) => Outcome::Pass,
+    }
+}
+
+fn check_local(fixture: &SpecFixture, test: &SpecTest) -> Outcome {
+    let error = static_error(fixture, &test.bundle);
+    match (test.should_fail, error) {
+        (false, Some(err)) => {
+            return Outcome::Fail(format!("expected to succeed, but failed: {err}"))
+        }
+        (true, None) => {
+            return Outcome::Skip("failure is only detectable by executing the bundle".to_string())
+        }
+        (true, Some(_)) => return Outcome::Pass,
+        (false, None) => {}
+    }
+
+    if test.extracted_refunds.is_empty() {
+        return Outcome::Pass;
+    }
+    match extracted_refunds(&test.bundle) {
+        Ok(refunds) => {
+            let expected: Vec<_> = test
+                .extracted_refunds
+                .iter()
+                .map(|refund| (refund.percent, refund.refund_split.clone()))
+                .collect();
+            if refunds == expected {
+                Outcome::Pass
+            } else {
+                Outcome::Fail(format!(
+                    "expected refunds {expected:?}, derived {refunds:?}"
+                ))
+            }
+        }
+        Err(err) => Outcome::Fail(format!("failed to derive refunds: {err}")),
+    }
+}
+
+/// Returns the first error found without executing the bundle
+fn static_error(fixture: &SpecFixture, bundle: &Bundle) -> Option<String> {
+    if let Err(err) = bundle.validate() {
+        return Some(err.to_string());
+    }
+    let txs = match bundle.decode_transactions() {
+        Ok(txs) => txs,
+        Err(err) => return Some(err.to_string()),
+    };
+
+    let mut nonces: HashMap<Address, U256> = HashMap::new();
+    for tx in txs {
+        let nonce = nonces
+            .entry(tx.from)
+            .or_insert_with(|| fixture.genesis_nonce(&tx.from).as_u64().into());
+        if tx.nonce != *nonce {
+            return Some(format!(
+                "nonce mismatch for {:?}: expected {nonce}, got {}",
+                tx.from, tx.nonce
+            ));
+        }
+        *nonce += U256::one();
+    }
+
+    None
+}
+
+/// Derives the percentage and recipients of each refund of the bundle and its nested bundles,
+/// innermost bundles first
+fn extracted_refunds(bundle: &Bundle) -> Result<Vec<(u32, BTreeMap<Address, u32>)>> {
+    let mut refunds = Vec::new();
+    for tx in &bundle.body {
+        if let BundleTx::Bundle { bundle } = tx {
+            refunds.extend(extracted_refunds(bundle)?);
+        }
+    }
+
+    let refund = bundle
+        .validity
+        .as_ref()
+        .and_then(|validity| validity.refund.as_deref())
+        .unwrap_or_default();
+    for refund in refund {
+        let entry = bundle
+            .body
+            .get(refund.body_idx)
+            .context("refund body_idx out of range")?;
+        refunds.push((refund.percent, refund_split(entry)?));
+    }
+
+    Ok(refunds)
+}
+
+/// Recipients of a refund paid for `entry`: the refund config of a nested bundle, or the sender of
+/// its first transaction
+fn refund_split(entry: &BundleTx) -> Result<BTreeMap<Address, u32>> {
+    match entry {
+        BundleTx::Bundle { bundle } => {
+            if let Some(config) = bundle
+                .validity
+                .as_ref()
+                .and_then(|validity| validity.refund_config.as_deref())
+            {
+                return config
+                    .iter()
+                    .map(|config| Ok((config.address.parse()?, config.percent)))
+                    .collect();
+            }
+            refund_split(bundle.body.first().context("nested bundle is empty")?)
+        }
+        tx => {
+            let sender = tx
+                .decode()?
+                .context("refunds for tx hashes require simulation")?
+                .from;
+            Ok(BTreeMap::from([(sender, 100)]))
+        }
+    }
+}
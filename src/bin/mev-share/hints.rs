@@ -0,0 +1,175 @@
+//! Filtering and printing of hints for the `listen`, `record`, `replay` and `history` commands
+use clap::{Args, ValueEnum};
+use ethers::types::{Address, Bytes, H256};
+use mev_share_client::types::{EventHistory, PendingTxOrBundle};
+use serde::Serialize;
+
+/// How hints are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One row per hint
+    #[default]
+    Table,
+    /// One JSON object per line
+    Json,
+}
+
+/// Filters selecting the hints that are printed or recorded. A hint matches if it matches all
+/// given filters.
+#[derive(Debug, Clone, Default, Args)]
+pub struct HintFilter {
+    /// Only hints with a transaction to one of these addresses
+    #[arg(long = "to", value_name = "ADDRESS")]
+    pub to: Vec<Address>,
+    /// Only hints with a transaction calling one of these 4-byte function selectors
+    #[arg(long = "selector", value_name = "SELECTOR")]
+    pub selectors: Vec<Bytes>,
+    /// Only hints with a log carrying one of these topics, e.g. an event signature
+    #[arg(long = "topic", value_name = "TOPIC")]
+    pub topics: Vec<H256>,
+    /// Only hints that share logs
+    #[arg(long)]
+    pub with_logs: bool,
+}
+
+/// The parts of a hint that are filtered on and shown in the table
+#[derive(Debug, Clone, Default)]
+pub struct HintSummary {
+    kind: &'static str,
+    block: Option<u64>,
+    hash: H256,
+    txs: Vec<(Option<Address>, Option<Bytes>)>,
+    topics: Vec<H256>,
+    logs: usize,
+}
+
+impl From<&PendingTxOrBundle> for HintSummary {
+    fn from(hint: &PendingTxOrBundle) -> Self {
+        match hint {
+            PendingTxOrBundle::Tx(tx) => {
+                let logs = tx.logs.as_deref().unwrap_or_default();
+                let selector = tx
+                    .function_selector
+                    .as_ref()
+                    .map(|selector| Bytes::from(selector.0.to_vec()));
+                Self {
+                    kind: "tx",
+                    block: None,
+                    hash: tx.hash,
+                    txs: if tx.to.is_some() || selector.is_some() {
+                        vec![(tx.to, selector)]
+                    } else {
+                        Vec::new()
+                    },
+                    topics: logs.iter().flat_map(|log| log.topics.clone()).collect(),
+                    logs: logs.len(),
+                }
+            }
+            PendingTxOrBundle::Bundle(bundle) => {
+                let logs = bundle.logs.as_deref().unwrap_or_default();
+                Self {
+                    kind: "bundle",
+                    block: None,
+                    hash: bundle.hash,
+                    txs: bundle
+                        .transactions
+                        .iter()
+                        .flatten()
+                        .map(|tx| {
+                            (
+                                Some(tx.to),
+                                Some(Bytes::from(tx.function_selector.0.to_vec())),
+                            )
+                        })
+                        .collect(),
+                    topics: logs.iter().flat_map(|log| log.topics.clone()).collect(),
+                    logs: logs.len(),
+                }
+            }
+        }
+    }
+}
+
+impl From<&EventHistory> for HintSummary {
+    fn from(event: &EventHistory) -> Self {
+        let logs = event.hint.logs.as_deref().unwrap_or_default();
+        let txs = event.hint.txs.as_deref().unwrap_or_default();
+        Self {
+            kind: if txs.len() > 1 { "bundle" } else { "tx" },
+            block: Some(event.block),
+            hash: event.hint.hash,
+            txs: txs
+                .iter()
+                .map(|tx| (tx.to, tx.function_selector.clone()))
+                .collect(),
+            topics: logs.iter().flat_map(|log| log.topics.clone()).collect(),
+            logs: logs.len(),
+        }
+    }
+}
+
+impl HintFilter {
+    /// Returns true if the hint passes all filters
+    pub fn matches(&self, hint: &HintSummary) -> bool {
+        (self.to.is_empty()
+            || hint
+                .txs
+                .iter()
+                .any(|(to, _)| to.is_some_and(|to| self.to.contains(&to))))
+            && (self.selectors.is_empty()
+                || hint.txs.iter().any(|(_, selector)| {
+                    selector
+                        .as_ref()
+                        .is_some_and(|selector| self.selectors.contains(selector))
+                }))
+            && (self.topics.is_empty() || hint.topics.iter().any(|t| self.topics.contains(t)))
+            && (!self.with_logs || hint.logs > 0)
+    }
+}
+
+/// Prints hints as table rows or JSON lines
+#[derive(Debug)]
+pub struct Printer {
+    format: OutputFormat,
+    printed_header: bool,
+}
+
+impl Printer {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            printed_header: false,
+        }
+    }
+
+    /// Prints `hint`, the table row is built from its `summary`
+    pub fn print<T: Serialize>(&mut self, hint: &T, summary: &HintSummary) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string(hint)?),
+            OutputFormat::Table => {
+                if !self.printed_header {
+                    println!(
+                        "{:<10} {:<6} {:<66} {:>3} {:<42} {:<10} {:>4}",
+                        "BLOCK", "KIND", "HASH", "TXS", "TO", "SELECTOR", "LOGS"
+                    );
+                    self.printed_header = true;
+                }
+
+                let (to, selector) = summary.txs.first().cloned().unwrap_or_default();
+                println!(
+                    "{:<10} {:<6} {:<66} {:>3} {:<42} {:<10} {:>4}",
+                    summary
+                        .block
+                        .map_or_else(|| "-".to_string(), |block| block.to_string()),
+                    summary.kind,
+                    format!("{:?}", summary.hash),
+                    summary.txs.len(),
+                    to.map_or_else(|| "-".to_string(), |to| format!("{to:?}")),
+                    selector.map_or_else(|| "-".to_string(), |selector| selector.to_string()),
+                    summary.logs,
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,477 @@
+//! `mev-share` command-line client, built with the `cli` feature
+//!
+//! The client is configured like [`MatchmakerClient::from_config`]: from the file given with
+//! `--config`, overridden by `MEV_SHARE_` environment variables. Commands sending or simulating
+//! bundles and transactions or reading searcher stats fail without an authentication key, the
+//! read-only `listen`, `record` and `history` run with a random key if none is configured.
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ethers::{
+    signers::LocalWallet,
+    types::{Bytes, H256, U64},
+};
+use hints::{HintFilter, HintSummary, OutputFormat, Printer};
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::{AuthKeySource, MatchmakerConfig},
+    telemetry,
+    types::{
+        Bundle, EventHistoryParams, HintPreference, PendingTxOrBundle, SimBundleOverrides,
+        StreamingEventTypes, TransactionOptions,
+    },
+};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::{Path, PathBuf},
+};
+use tokio::sync::mpsc;
+
+mod conformance;
+mod hints;
+
+/// Inspect and interact with the Flashbots MEV-Share matchmaker
+#[derive(Debug, Parser)]
+#[command(name = "mev-share", version)]
+struct Cli {
+    /// Client config file, `.toml` or `.json`
+    #[arg(long, short, global = true, env = "MEV_SHARE_CONFIG")]
+    config: Option<PathBuf>,
+    /// Network to connect to, e.g. "sepolia". Overrides the config
+    #[arg(long, global = true)]
+    network: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Stream hints of pending transactions or bundles
+    Listen(ListenArgs),
+    /// Send a bundle with mev_sendBundle
+    SendBundle(SendBundleArgs),
+    /// Simulate a bundle with mev_simBundle
+    SimBundle(SimBundleArgs),
+    /// Send a signed transaction with eth_sendPrivateTransaction
+    SendTx(SendTxArgs),
+    /// Query past hints of the event stream
+    History(HistoryArgs),
+    /// Show statistics of the auth key or of a bundle
+    #[command(subcommand)]
+    Stats(StatsCommand),
+    /// Record hints to a JSON lines file
+    Record(RecordArgs),
+    /// Print hints recorded with `record`
+    Replay(ReplayArgs),
+    /// Run the spec test vectors against an endpoint or the local checks
+    Conformance(conformance::ConformanceArgs),
+}
+
+/// Event types of the stream
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+enum EventType {
+    /// Pending transactions
+    #[default]
+    Tx,
+    /// Pending bundles
+    Bundle,
+}
+
+impl From<EventType> for StreamingEventTypes {
+    fn from(event_type: EventType) -> Self {
+        match event_type {
+            EventType::Tx => StreamingEventTypes::Transaction,
+            EventType::Bundle => StreamingEventTypes::Bundle,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct ListenArgs {
+    /// Type of the hints to stream
+    #[arg(long, value_enum, default_value_t)]
+    event: EventType,
+    #[command(flatten)]
+    filter: HintFilter,
+    /// Exit after this many matching hints
+    #[arg(long)]
+    count: Option<usize>,
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Args)]
+struct BundleFileArgs {
+    /// Bundle JSON file, either a bundle or a test case of the form `{"name": .., "bundle": ..}`
+    file: PathBuf,
+    /// Overrides the bundle's target block
+    #[arg(long)]
+    block: Option<u64>,
+    /// Overrides the bundle's max block
+    #[arg(long)]
+    max_block: Option<u64>,
+}
+
+impl BundleFileArgs {
+    fn load(&self) -> Result<Bundle> {
+        let mut bundle = load_bundle(&self.file)?;
+        if let Some(block) = self.block {
+            bundle.inclusion.block = block.into();
+        }
+        if let Some(max_block) = self.max_block {
+            bundle.inclusion.max_block = Some(max_block.into());
+        }
+        Ok(bundle)
+    }
+}
+
+#[derive(Debug, Args)]
+struct SendBundleArgs {
+    #[command(flatten)]
+    bundle: BundleFileArgs,
+    /// Send the bundle without validating it first
+    #[arg(long)]
+    no_validate: bool,
+}
+
+#[derive(Debug, Args)]
+struct SimBundleArgs {
+    #[command(flatten)]
+    bundle: BundleFileArgs,
+    /// Block whose state the simulation is based on
+    #[arg(long)]
+    parent_block: Option<u64>,
+    /// Number of the simulated block
+    #[arg(long)]
+    block_number: Option<u64>,
+    /// Timestamp of the simulated block
+    #[arg(long)]
+    timestamp: Option<u64>,
+    /// Timeout of the simulation in seconds
+    #[arg(long)]
+    timeout: Option<u64>,
+}
+
+/// Hints of a private transaction shared with searchers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Hint {
+    Calldata,
+    ContractAddress,
+    FunctionSelector,
+    Logs,
+    TxHash,
+    Hash,
+}
+
+#[derive(Debug, Args)]
+struct SendTxArgs {
+    /// Hex encoded signed transaction
+    tx: Bytes,
+    /// Last block the transaction can be included in
+    #[arg(long)]
+    max_block: Option<u64>,
+    /// Hints shared with searchers, the matchmaker's defaults are used if none are given
+    #[arg(long = "hint", value_enum, value_name = "HINT")]
+    hints: Vec<Hint>,
+    /// Builders the transaction is sent to, in addition to Flashbots
+    #[arg(long = "builder", value_name = "BUILDER")]
+    builders: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct HistoryArgs {
+    /// Show the range and size of the history instead of events
+    #[arg(long)]
+    info: bool,
+    /// First block of the events
+    #[arg(long)]
+    block_start: Option<u64>,
+    /// Last block of the events
+    #[arg(long)]
+    block_end: Option<u64>,
+    /// Earliest timestamp of the events
+    #[arg(long)]
+    timestamp_start: Option<u64>,
+    /// Latest timestamp of the events
+    #[arg(long)]
+    timestamp_end: Option<u64>,
+    /// Maximum number of events
+    #[arg(long)]
+    limit: Option<u64>,
+    /// Number of events to skip
+    #[arg(long)]
+    offset: Option<u64>,
+    #[command(flatten)]
+    filter: HintFilter,
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Subcommand)]
+enum StatsCommand {
+    /// Reputation and payments of the auth key
+    User {
+        /// The current block number
+        #[arg(long)]
+        block: u64,
+    },
+    /// Simulation and builder statistics of a bundle sent with the auth key
+    Bundle {
+        /// Bundle hash returned when sending the bundle
+        bundle_hash: H256,
+        /// Block the bundle was sent for
+        #[arg(long)]
+        block: u64,
+    },
+}
+
+#[derive(Debug, Args)]
+struct RecordArgs {
+    /// File the hints are appended to, one JSON object per line
+    file: PathBuf,
+    /// Type of the hints to record
+    #[arg(long, value_enum, default_value_t)]
+    event: EventType,
+    #[command(flatten)]
+    filter: HintFilter,
+    /// Exit after this many matching hints
+    #[arg(long)]
+    count: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct ReplayArgs {
+    /// File written by `record`
+    file: PathBuf,
+    #[command(flatten)]
+    filter: HintFilter,
+    /// Output format
+    #[arg(long, short, value_enum, default_value_t)]
+    output: OutputFormat,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _ = telemetry::init_subscriber();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Replay(args) => return replay(args),
+        Command::Conformance(args) => {
+            if !conformance::run(args).await? {
+                std::process::exit(1);
+            }
+        }
+        Command::Listen(args) => {
+            let client = client(cli.config.as_deref(), cli.network, false)?;
+            let mut printer = Printer::new(args.output);
+            stream(&client, args.event, &args.filter, args.count, |hint| {
+                printer.print(hint, &HintSummary::from(hint))
+            })
+            .await?;
+        }
+        Command::Record(args) => {
+            let client = client(cli.config.as_deref(), cli.network, false)?;
+            let file = File::options()
+                .create(true)
+                .append(true)
+                .open(&args.file)
+                .with_context(|| format!("failed to open {}", args.file.display()))?;
+            let mut file = LineWriter::new(file);
+            stream(&client, args.event, &args.filter, args.count, |hint| {
+                writeln!(file, "{}", serde_json::to_string(hint)?)?;
+                Ok(())
+            })
+            .await?;
+        }
+        Command::SendBundle(args) => {
+            let client = client(cli.config.as_deref(), cli.network, true)?
+                .with_bundle_validation(!args.no_validate);
+            print_json(&client.send_bundle(&args.bundle.load()?).await?)?;
+        }
+        Command::SimBundle(args) => {
+            let client = client(cli.config.as_deref(), cli.network, true)?;
+            let overrides = SimBundleOverrides {
+                parent_block: args.parent_block.map(U64::from),
+                block_number: args.block_number.map(U64::from),
+                timestamp: args.timestamp,
+                timeout: args.timeout,
+                ..Default::default()
+            };
+            print_json(&client.sim_bundle(&args.bundle.load()?, &overrides).await?)?;
+        }
+        Command::SendTx(args) => {
+            let client = client(cli.config.as_deref(), cli.network, true)?;
+            let options = TransactionOptions {
+                hints: (!args.hints.is_empty()).then(|| hint_preference(&args.hints)),
+                max_block_number: args.max_block.map(U64::from),
+                builders: (!args.builders.is_empty()).then_some(args.builders),
+            };
+            print_json(&client.send_private_transaction(args.tx, &options).await?)?;
+        }
+        Command::History(args) => {
+            let client = client(cli.config.as_deref(), cli.network, false)?;
+            if args.info {
+                print_json(&client.get_event_history_info().await?)?;
+                return Ok(());
+            }
+
+            let params = EventHistoryParams {
+                block_start: args.block_start,
+                block_end: args.block_end,
+                timestamp_start: args.timestamp_start,
+                timestamp_end: args.timestamp_end,
+                limit: args.limit,
+                offset: args.offset,
+            };
+            let mut printer = Printer::new(args.output);
+            for event in client.get_event_history(&params).await? {
+                let summary = HintSummary::from(&event);
+                if args.filter.matches(&summary) {
+                    printer.print(&event, &summary)?;
+                }
+            }
+        }
+        Command::Stats(StatsCommand::User { block }) => {
+            let client = client(cli.config.as_deref(), cli.network, true)?;
+            print_json(&client.get_user_stats(block.into()).await?)?;
+        }
+        Command::Stats(StatsCommand::Bundle { bundle_hash, block }) => {
+            let client = client(cli.config.as_deref(), cli.network, true)?;
+            print_json(&client.get_bundle_stats(bundle_hash, block.into()).await?)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the client from the config file and environment
+///
+/// * `needs_auth` - Whether the command signs requests that are attributed to the auth key, which
+///   fails if none is configured. Other commands run with a random key.
+fn client(
+    path: Option<&Path>,
+    network: Option<String>,
+    needs_auth: bool,
+) -> Result<MatchmakerClient<LocalWallet>> {
+    let mut config = match path {
+        Some(path) => MatchmakerConfig::from_file(path)?.with_env_overrides()?,
+        None => MatchmakerConfig::from_env()?,
+    };
+    if let Some(network) = network {
+        config.network = network;
+    }
+    if config.auth_key.is_none() {
+        if needs_auth {
+            bail!("no auth key configured, set auth_key in the config or MEV_SHARE_AUTH_KEY");
+        }
+        config.auth_key = Some(random_auth_key());
+    }
+
+    MatchmakerClient::from_config(&config)
+}
+
+/// A newly generated authentication key, for requests that are not attributed to a searcher
+fn random_auth_key() -> AuthKeySource {
+    let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+    AuthKeySource::Key(hex::encode(wallet.signer().to_bytes()))
+}
+
+/// Streams hints matching `filter` to `handle` until `count` hints were handled or the stream ends
+///
+/// Hints received before the stream ended are handled before returning.
+async fn stream<F>(
+    client: &MatchmakerClient<LocalWallet>,
+    event: EventType,
+    filter: &HintFilter,
+    count: Option<usize>,
+    mut handle: F,
+) -> Result<()>
+where
+    F: FnMut(&PendingTxOrBundle) -> Result<()>,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let events = client.on(event.into(), move |hint| {
+        // the receiver is only dropped once the stream is no longer polled
+        let _ = sender.send(hint);
+    });
+    tokio::pin!(events);
+
+    let mut handled = 0;
+    let mut ended = None;
+    while Some(handled) != count {
+        let hint = match ended {
+            // the stream ended, handle the hints it sent before that are still buffered
+            Some(_) => match receiver.try_recv() {
+                Ok(hint) => hint,
+                Err(_) => break,
+            },
+            None => tokio::select! {
+                result = &mut events => {
+                    ended = Some(result);
+                    continue;
+                }
+                Some(hint) = receiver.recv() => hint,
+            },
+        };
+        if filter.matches(&HintSummary::from(&hint)) {
+            handle(&hint)?;
+            handled += 1;
+        }
+    }
+
+    ended.unwrap_or(Ok(()))
+}
+
+fn replay(args: ReplayArgs) -> Result<()> {
+    let file = File::open(&args.file)
+        .with_context(|| format!("failed to open {}", args.file.display()))?;
+    let mut printer = Printer::new(args.output);
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let hint: PendingTxOrBundle = serde_json::from_str(&line)
+            .with_context(|| format!("invalid hint on line {}", number + 1))?;
+        let summary = HintSummary::from(&hint);
+        if args.filter.matches(&summary) {
+            printer.print(&hint, &summary)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a bundle in the format of the `bundle` entries of the spec tests
+fn load_bundle(path: &Path) -> Result<Bundle> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read bundle from {}", path.display()))?;
+    let mut value: serde_json::Value = serde_json::from_str(&contents)?;
+    if let Some(bundle) = value.get_mut("bundle") {
+        value = bundle.take();
+    }
+
+    serde_json::from_value(value).context("invalid bundle")
+}
+
+fn hint_preference(hints: &[Hint]) -> HintPreference {
+    let shared = |hint| Some(hints.contains(&hint));
+    HintPreference {
+        calldata: shared(Hint::Calldata),
+        contract_address: shared(Hint::ContractAddress),
+        function_selector: shared(Hint::FunctionSelector),
+        logs: shared(Hint::Logs),
+        tx_hash: shared(Hint::TxHash),
+        hash: shared(Hint::Hash),
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
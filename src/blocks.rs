@@ -0,0 +1,84 @@
+//! Sources of new block numbers that drive per-block work such as bundle resubmission, and of
+//! the transactions of a block
+use anyhow::Result;
+use ethers::{
+    providers::Middleware,
+    types::{BlockId, TxHash, U64},
+};
+use futures_util::{
+    future::{BoxFuture, FutureExt},
+    stream::{self, BoxStream, StreamExt},
+};
+use std::time::Duration;
+use tracing::warn;
+
+/// A source of new block numbers
+pub trait BlockSource: Send + Sync {
+    /// Returns a stream yielding the number of every new block, in increasing order.
+    ///
+    /// Blocks may be skipped if several are produced between two items, consumers should not rely
+    /// on seeing every block number.
+    fn block_numbers(&self) -> BoxStream<'_, U64>;
+}
+
+/// A source of the transactions included in a block
+pub trait BlockContents: Send + Sync {
+    /// Returns the hashes of the transactions of block `number` in block order, or `None` if the
+    /// block is not known yet
+    fn block_transactions(&self, number: U64) -> BoxFuture<'_, Result<Option<Vec<TxHash>>>>;
+}
+
+/// Polls a provider for the latest block number and reads block transactions from it
+#[derive(Debug, Clone)]
+pub struct PollingBlockSource<M> {
+    provider: M,
+    interval: Duration,
+}
+
+impl<M: Middleware> PollingBlockSource<M> {
+    /// Creates a source polling `provider` every `interval`
+    ///
+    /// * `provider` - Provider to read the latest block number from
+    /// * `interval` - Time between two polls, e.g. a fraction of the block time
+    pub fn new(provider: M, interval: Duration) -> Self {
+        Self { provider, interval }
+    }
+}
+
+impl<M> BlockSource for PollingBlockSource<M>
+where
+    M: Middleware + Send + Sync,
+{
+    fn block_numbers(&self) -> BoxStream<'_, U64> {
+        stream::unfold(None, move |last: Option<U64>| async move {
+            loop {
+                match self.provider.get_block_number().await {
+                    Ok(number) if Some(number) > last => {
+                        return Some((number, Some(number)));
+                    }
+                    Ok(_) => {}
+                    Err(err) => warn!(error = %err, "Failed to poll block number"),
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+        .boxed()
+    }
+}
+
+impl<M> BlockContents for PollingBlockSource<M>
+where
+    M: Middleware + Send + Sync,
+{
+    fn block_transactions(&self, number: U64) -> BoxFuture<'_, Result<Option<Vec<TxHash>>>> {
+        async move {
+            let block = self
+                .provider
+                .get_block(BlockId::Number(number.into()))
+                .await
+                .map_err(|err| anyhow::anyhow!("failed to get block {number}: {err}"))?;
+            Ok(block.map(|block| block.transactions))
+        }
+        .boxed()
+    }
+}
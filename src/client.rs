@@ -1,33 +1,58 @@
 //! A Flashbots client for interacting with the Flashbots Matchmaker service
 //! based on https://github.com/flashbots/matchmaker-ts
+use crate::config::{MatchmakerConfig, RateLimitConfig, RetryConfig};
+use crate::fanout::{https_client, HttpsClient};
+use crate::metrics::{self, RequestMetrics, RequestMetricsLayer};
+use crate::rate_limit::{RateLimit, RateLimitLayer};
+use crate::retry::{self, Retry, RetryLayer};
 use crate::signer_middleware::{FlashbotsSigner, FlashbotsSignerLayer};
 use crate::types::{
-    Bundle, MatchMakerNetwork, PendingBundle, PendingTransaction, PendingTxOrBundle,
-    SendBundleResponse, StreamingEventTypes, SupportedNetworks,
+    Bundle, BundleStats, BundleStatsParams, CallBundleParams, CallBundleResponse,
+    CancelBundleParams, CancelPrivateTransactionParams, EventHistory, EventHistoryInfo,
+    EventHistoryParams, FlashbotsBundle, MatchMakerNetwork, PendingBundle, PendingTransaction,
+    PendingTxOrBundle, PrivacyParams, SendBundleResponse, SendPrivateTransactionParams,
+    SimBundleOverrides, SimBundleResponse, StreamingEventTypes, SupportedNetworks,
+    TransactionOptions, UserStats, UserStatsParams, HOLESKY_CHAIN_ID,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::{Bytes, Chain, TxHash, H256, U64},
 };
-use anyhow::Result;
-use ethers::{signers::Signer, types::Chain};
 use futures_util::StreamExt;
 use jsonrpsee::{core::client::ClientT, http_client};
-use log::{error, info};
-use mev_share_rs::{sse::Event, EventClient};
+use mev_share_rs::{
+    sse::{Event, SseError},
+    EventClient,
+};
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tower::ServiceBuilder;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing::{debug, error, info, instrument, warn};
 
-type FlashbotsSignerClient<S> =
-    http_client::HttpClient<FlashbotsSigner<S, http_client::transport::HttpBackend>>;
+type FlashbotsSignerClient<S> = http_client::HttpClient<
+    RateLimit<Retry<RequestMetrics<FlashbotsSigner<S, http_client::transport::HttpBackend>>>>,
+>;
 
 /// Struct representing a client for interacting with the Flashbots Matchmaker service
-#[allow(dead_code)]
-pub struct MatchmakerClient<'a, S> {
-    signer_client: FlashbotsSignerClient<S>,
-    network: MatchMakerNetwork<'a>,
+///
+/// The client is cheap to clone, clones share the underlying HTTP and event stream clients.
+#[derive(Clone)]
+pub struct MatchmakerClient<S> {
+    signer_client: Arc<FlashbotsSignerClient<S>>,
+    history_client: HttpsClient,
+    network: Arc<MatchMakerNetwork>,
     event_client: EventClient,
+    validate_bundles: bool,
+    default_privacy: Option<PrivacyParams>,
+    block_time: Duration,
 }
 
-impl<'a, S> MatchmakerClient<'a, S>
+impl<S> MatchmakerClient<S>
 where
     S: Signer + Clone + 'static,
 {
@@ -36,68 +61,149 @@ where
     /// * `auth_signer` - A Signer used for signing tx
     /// * `network` - The network that the client will connect to
     /// * `event_client` - A client for handling incoming events
-    #[allow(clippy::wrong_self_convention)]
     fn new(
-        self,
         auth_signer: S,
-        network: MatchMakerNetwork<'a>,
+        network: MatchMakerNetwork,
+        event_client: EventClient,
+    ) -> MatchmakerClient<S> {
+        Self::build(
+            auth_signer,
+            network,
+            event_client,
+            http_client::HttpClientBuilder::default(),
+            RetryConfig::default(),
+            RateLimitConfig::default(),
+        )
+        .unwrap()
+    }
+
+    /// Constructs a new `MatchmakerClient` whose HTTP client is configured by `http_builder`,
+    /// retries failed requests according to `retry` and throttles requests according to `rate_limit`.
+    /// Retries are not throttled.
+    fn build(
+        auth_signer: S,
+        network: MatchMakerNetwork,
         event_client: EventClient,
-    ) -> MatchmakerClient<'a, S> {
+        http_builder: http_client::HttpClientBuilder,
+        retry: RetryConfig,
+        rate_limit: RateLimitConfig,
+    ) -> Result<MatchmakerClient<S>> {
+        rate_limit.validate()?;
         let signing_middleware = FlashbotsSignerLayer::new(Arc::new(auth_signer));
+        let block_time = retry.block_time();
 
-        let service_builder = ServiceBuilder::new().layer(signing_middleware);
+        let service_builder = ServiceBuilder::new()
+            .layer(RateLimitLayer::new(rate_limit))
+            .layer(RetryLayer::new(retry))
+            .layer(RequestMetricsLayer)
+            .layer(signing_middleware);
 
-        let http_client = http_client::HttpClientBuilder::default()
+        let http_client = http_builder
             .set_middleware(service_builder)
-            .build(network.api_url)
-            .unwrap();
+            .build(&network.api_url)?;
 
-        Self {
-            signer_client: http_client,
-            network,
+        Ok(Self {
+            signer_client: Arc::new(http_client),
+            history_client: https_client(),
+            network: Arc::new(network),
             event_client,
-        }
+            validate_bundles: true,
+            default_privacy: None,
+            block_time,
+        })
+    }
+
+    /// Enables or disables client-side validation of bundles in [`Self::send_bundle`]. Enabled by default.
+    ///
+    /// * `enabled` - Whether bundles are validated before being sent
+    pub fn with_bundle_validation(mut self, enabled: bool) -> MatchmakerClient<S> {
+        self.validate_bundles = enabled;
+        self
+    }
+
+    /// Sets the privacy parameters used for bundles that do not specify their own
+    ///
+    /// * `privacy` - Hints and builders applied to bundles without privacy parameters
+    pub fn with_default_privacy(mut self, privacy: PrivacyParams) -> MatchmakerClient<S> {
+        self.default_privacy = Some(privacy);
+        self
     }
 
     /// Connect to Flashbots Mainnet Matchmaker
     ///
     /// * `auth_signer` - A Signer used for signing tx
-    pub fn use_ethereum_mainnet(mut self, auth_signer: S) -> MatchmakerClient<'a, S> {
+    pub fn use_ethereum_mainnet(auth_signer: S) -> MatchmakerClient<S> {
         let supported_networks = SupportedNetworks::new();
-        self.network = supported_networks
+        let network = supported_networks
             .get_network(Chain::Mainnet as u64)
             .unwrap();
         let event_client = EventClient::default();
-        let network = self.network.clone();
-        self.new(auth_signer, network, event_client)
+        Self::new(auth_signer, network, event_client)
     }
 
     /// Connect to Flashbots Goerli Matchmaker
     ///     
     /// * `auth_signer` - A Signer used for signing tx
-    pub fn use_ethereum_goerli(mut self, auth_signer: S) -> MatchmakerClient<'a, S> {
+    #[deprecated(
+        note = "Goerli is deprecated, use `use_ethereum_sepolia` or `use_ethereum_holesky` instead"
+    )]
+    pub fn use_ethereum_goerli(auth_signer: S) -> MatchmakerClient<S> {
         let supported_networks = SupportedNetworks::new();
-        self.network = supported_networks
+        let network = supported_networks
             .get_network(Chain::Goerli as u64)
             .unwrap();
         let event_client = EventClient::default();
-        let network = self.network.clone();
-        self.new(auth_signer, network, event_client)
+        Self::new(auth_signer, network, event_client)
+    }
+
+    /// Connect to Flashbots Sepolia Matchmaker
+    ///
+    /// * `auth_signer` - A Signer used for signing tx
+    pub fn use_ethereum_sepolia(auth_signer: S) -> MatchmakerClient<S> {
+        let supported_networks = SupportedNetworks::new();
+        let network = supported_networks
+            .get_network(Chain::Sepolia as u64)
+            .unwrap();
+        let event_client = EventClient::default();
+        Self::new(auth_signer, network, event_client)
+    }
+
+    /// Connect to Flashbots Holesky Matchmaker
+    ///
+    /// * `auth_signer` - A Signer used for signing tx
+    pub fn use_ethereum_holesky(auth_signer: S) -> MatchmakerClient<S> {
+        let supported_networks = SupportedNetworks::new();
+        let network = supported_networks.get_network(HOLESKY_CHAIN_ID).unwrap();
+        let event_client = EventClient::default();
+        Self::new(auth_signer, network, event_client)
+    }
+
+    /// Connect to a network that is not predefined, e.g. a local Matchmaker
+    ///
+    /// * `auth_signer` - A Signer used for signing tx
+    /// * `network` - The network to connect to
+    pub fn use_network(auth_signer: S, network: MatchMakerNetwork) -> MatchmakerClient<S> {
+        let event_client = EventClient::default();
+        Self::new(auth_signer, network, event_client)
     }
 
     /// Connect to supported networks by specifying a network with a `chain_id`
     ///     
     /// * `auth_signer` - A Signer used for signing tx
     /// * `chain_id` - ID of the chain to connect to
-    pub async fn from_network(mut self, auth_signer: S, chain_id: u64) -> MatchmakerClient<'a, S> {
+    pub async fn from_network(auth_signer: S, chain_id: u64) -> MatchmakerClient<S> {
         let supported_networks = SupportedNetworks::new();
         if !supported_networks.is_supported(chain_id) {
             panic!("Chain ID {} is not supported", chain_id);
         }
-        self.network = supported_networks.get_network(chain_id).unwrap();
+        let network = supported_networks.get_network(chain_id).unwrap();
         let event_client = EventClient::default();
-        let network = self.network.clone();
-        self.new(auth_signer, network, event_client)
+        Self::new(auth_signer, network, event_client)
+    }
+
+    /// Returns the network the client is connected to
+    pub fn network(&self) -> &MatchMakerNetwork {
+        &self.network
     }
 
     /// Registers the provided callback to be called when a new MEV-Share transaction is received.
@@ -126,66 +232,346 @@ where
 
     /// Starts listening to the Matchmaker event stream and registers the given callback to be invoked when the given event type is received
     ///
+    /// Returns once the stream ends, or an error if connecting to the stream failed.
+    ///
     /// * `event_type` - Type of the event to listen for
     /// * `callback` - Function that will be called when a new event is received
-    pub async fn on<F>(&self, event_type: StreamingEventTypes, callback: F)
+    #[instrument(skip_all, fields(stream_url = %self.network.stream_url, event_type = event_type.as_str()))]
+    pub async fn on<F>(&self, event_type: StreamingEventTypes, callback: F) -> Result<()>
     where
         F: FnMut(PendingTxOrBundle) + Send + Sync + 'static,
     {
-        tracing_subscriber::registry()
-            .with(fmt::layer())
-            .with(EnvFilter::from_default_env())
-            .init();
-
-        let mut stream = self
-            .event_client
-            .events(self.network.stream_url)
-            .await
-            .unwrap();
+        let stream = self.event_client.events(&self.network.stream_url).await;
+        metrics::record_stream_connect(stream.is_ok());
+        let mut stream = stream.map_err(|err| {
+            error!(error = %err, "Failed to connect to the event stream");
+            anyhow!("failed to connect to the event stream: {err}")
+        })?;
 
-        info!(
-            "Connected to Flashbots Matchmaker at {}",
-            self.network.stream_url
-        );
+        info!("Connected to Flashbots Matchmaker");
 
         let callback = Arc::new(Mutex::new(callback));
         let event_handler: Box<dyn Fn(Event) + Send + Sync> = match event_type {
-            StreamingEventTypes::Bundle => {
-                info!("Listening for Bundle events");
-                Box::new(|pending_event: Event| {
-                    self.on_bundle(&pending_event, &mut *callback.lock());
-                })
-            }
-            StreamingEventTypes::Transaction => {
-                info!("Listening for Bundle events");
-                Box::new(|pending_event: Event| {
-                    self.on_transaction(&pending_event, &mut *callback.lock());
-                })
-            }
+            StreamingEventTypes::Bundle => Box::new(|pending_event: Event| {
+                self.on_bundle(&pending_event, &mut *callback.lock());
+            }),
+            StreamingEventTypes::Transaction => Box::new(|pending_event: Event| {
+                self.on_transaction(&pending_event, &mut *callback.lock());
+            }),
         };
 
         // TODO: add Event enum to allow dynamic dispatch
         while let Some(event) = stream.next().await {
             match event {
                 Ok(e) => {
+                    debug!(hash = ?e.hash, "Received event");
+                    metrics::record_event(event_type.as_str());
+                    let start = Instant::now();
                     event_handler(e);
+                    metrics::observe_callback(start.elapsed());
+                }
+                Err(SseError::SerdeJsonError(e)) => {
+                    error!(error = ?e, "Failed to decode event");
+                    metrics::record_decode_error();
                 }
                 Err(e) => {
-                    error!("Error: {:?}", e);
+                    warn!(error = ?e, "Event stream failed, reconnecting");
+                    metrics::record_stream_reconnect();
                 }
             }
         }
+
+        Ok(())
     }
 
     /// Sends a bundle to mev-share
     ///
+    /// Bundles without privacy parameters get the client's defaults, see [`Self::with_default_privacy`].
+    /// The bundle is checked with [`Bundle::validate`] first unless validation was disabled with
+    /// [`Self::with_bundle_validation`]. Failed attempts are retried until the last block the
+    /// bundle targets is due, see [`RetryConfig::block_time_ms`].
+    ///
     /// * `bundle` - Params for the bundle to be sent
+    #[instrument(skip_all, fields(bundle_hash = ?bundle.hash(), block = %bundle.inclusion.block))]
     pub async fn send_bundle(&self, bundle: &Bundle) -> Result<SendBundleResponse> {
-        let response = self
+        let bundle = match (&bundle.privacy, &self.default_privacy) {
+            (None, Some(privacy)) => Cow::Owned(Bundle {
+                privacy: Some(privacy.clone()),
+                ..bundle.clone()
+            }),
+            _ => Cow::Borrowed(bundle),
+        };
+
+        if self.validate_bundles {
+            if let Err(err) = bundle.validate() {
+                warn!(error = %err, "Rejected invalid bundle");
+                return Err(err.into());
+            }
+        }
+
+        debug!("Sending bundle");
+        let request = self
+            .signer_client
+            .request::<SendBundleResponse, _>("mev_sendBundle", [bundle.as_ref()]);
+        let response = match retry::bundle_deadline(&bundle.inclusion, self.block_time) {
+            Some(deadline) => retry::with_deadline(deadline, request).await,
+            None => request.await,
+        }
+        .map_err(|err| {
+            warn!(error = %err, "Failed to send bundle");
+            err
+        })?;
+        info!("Sent bundle");
+
+        Ok(response)
+    }
+
+    /// Sends a bundle with the classic Flashbots bundle API, `eth_sendBundle`
+    ///
+    /// Use this for bundles that are not MEV-Share backruns, those are sent with [`Self::send_bundle`].
+    ///
+    /// * `bundle` - Params for the bundle to be sent
+    #[instrument(skip_all, fields(block = %bundle.block_number, txs = bundle.txs.len()))]
+    pub async fn send_flashbots_bundle(
+        &self,
+        bundle: &FlashbotsBundle,
+    ) -> Result<SendBundleResponse> {
+        debug!("Sending Flashbots bundle");
+        let response: SendBundleResponse = self
+            .signer_client
+            .request("eth_sendBundle", [bundle])
+            .await
+            .map_err(|err| {
+                warn!(error = %err, "Failed to send Flashbots bundle");
+                err
+            })?;
+        info!(bundle_hash = ?response.bundle_hash, "Sent Flashbots bundle");
+
+        Ok(response)
+    }
+
+    /// Simulates a bundle on top of the given state block with `eth_callBundle`
+    ///
+    /// * `params` - Transactions and blocks of the simulation
+    #[instrument(skip_all, fields(block = %params.block_number, txs = params.txs.len()))]
+    pub async fn call_bundle(&self, params: &CallBundleParams) -> Result<CallBundleResponse> {
+        let response: CallBundleResponse = self
             .signer_client
-            .request("mev_sendBundle", [bundle])
+            .request("eth_callBundle", [params])
             .await?;
+        debug!(
+            bundle_hash = ?response.bundle_hash,
+            total_gas_used = response.total_gas_used,
+            "Simulated bundle"
+        );
 
         Ok(response)
     }
+
+    /// Simulates a bundle with mev_simBundle
+    ///
+    /// Unlike [`Self::call_bundle`], this simulates MEV-Share bundles, including bundles that
+    /// backrun hints by their tx hash.
+    ///
+    /// * `bundle` - The bundle to simulate
+    /// * `overrides` - Overrides of the simulated block
+    #[instrument(skip_all, fields(bundle_hash = ?bundle.hash(), block = %bundle.inclusion.block))]
+    pub async fn sim_bundle(
+        &self,
+        bundle: &Bundle,
+        overrides: &SimBundleOverrides,
+    ) -> Result<SimBundleResponse> {
+        let response: SimBundleResponse = self
+            .signer_client
+            .request("mev_simBundle", (bundle, overrides))
+            .await?;
+        debug!(
+            success = response.success,
+            profit = %response.profit,
+            "Simulated bundle"
+        );
+
+        Ok(response)
+    }
+
+    /// Sends a signed transaction to the matchmaker with eth_sendPrivateTransaction
+    ///
+    /// Returns the hash of the transaction.
+    ///
+    /// * `tx` - Bytes of the signed transaction
+    /// * `options` - Hints, builders and maximum block of the transaction
+    #[instrument(skip_all, fields(max_block = ?options.max_block_number))]
+    pub async fn send_private_transaction(
+        &self,
+        tx: Bytes,
+        options: &TransactionOptions,
+    ) -> Result<TxHash> {
+        let tx_hash: TxHash = self
+            .signer_client
+            .request(
+                "eth_sendPrivateTransaction",
+                [SendPrivateTransactionParams::new(tx, options)],
+            )
+            .await
+            .map_err(|err| {
+                warn!(error = %err, "Failed to send private transaction");
+                err
+            })?;
+        info!(?tx_hash, "Sent private transaction");
+
+        Ok(tx_hash)
+    }
+
+    /// Cancels a private transaction so it is no longer sent to builders
+    ///
+    /// Returns false if the transaction could not be cancelled, e.g. because it was already included.
+    ///
+    /// * `tx_hash` - Hash of the private transaction to cancel
+    #[instrument(skip(self))]
+    pub async fn cancel_private_transaction(&self, tx_hash: TxHash) -> Result<bool> {
+        let cancelled: bool = self
+            .signer_client
+            .request(
+                "eth_cancelPrivateTransaction",
+                [CancelPrivateTransactionParams { tx_hash }],
+            )
+            .await?;
+        info!(cancelled, "Cancelled private transaction");
+
+        Ok(cancelled)
+    }
+
+    /// Cancels all bundles sent with the given replacement UUID, see [`Bundle::replacement_uuid`].
+    ///
+    /// Only supported by endpoints implementing `eth_cancelBundle`. Bundles that were already
+    /// included can not be cancelled.
+    ///
+    /// * `replacement_uuid` - Replacement UUID of the bundles to cancel
+    #[instrument(skip(self))]
+    pub async fn cancel_bundle(&self, replacement_uuid: &str) -> Result<()> {
+        let _: serde_json::Value = self
+            .signer_client
+            .request(
+                "eth_cancelBundle",
+                [CancelBundleParams {
+                    replacement_uuid: replacement_uuid.to_string(),
+                }],
+            )
+            .await?;
+        info!("Cancelled bundle");
+
+        Ok(())
+    }
+
+    /// Returns the reputation and payment statistics of the auth signer
+    ///
+    /// * `block_number` - The current block number
+    #[instrument(skip(self))]
+    pub async fn get_user_stats(&self, block_number: U64) -> Result<UserStats> {
+        let stats = self
+            .signer_client
+            .request(
+                "flashbots_getUserStatsV2",
+                [UserStatsParams { block_number }],
+            )
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Returns the simulation and builder statistics of a bundle sent by the auth signer
+    ///
+    /// * `bundle_hash` - Bundle hash returned by [`Self::send_bundle`]
+    /// * `block_number` - The block number the bundle was sent for
+    #[instrument(skip(self))]
+    pub async fn get_bundle_stats(
+        &self,
+        bundle_hash: H256,
+        block_number: U64,
+    ) -> Result<BundleStats> {
+        let stats = self
+            .signer_client
+            .request(
+                "flashbots_getBundleStatsV2",
+                [BundleStatsParams {
+                    bundle_hash,
+                    block_number,
+                }],
+            )
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Returns past hints of the event stream matching `params`
+    ///
+    /// The history is served by the event stream host and does not require authentication.
+    ///
+    /// * `params` - Block and time range and paging of the returned events
+    #[instrument(skip(self))]
+    pub async fn get_event_history(
+        &self,
+        params: &EventHistoryParams,
+    ) -> Result<Vec<EventHistory>> {
+        let query = params.to_query();
+        let path = if query.is_empty() {
+            "api/v1/history".to_string()
+        } else {
+            format!("api/v1/history?{query}")
+        };
+        self.get_history(&path).await
+    }
+
+    /// Returns the block and time range and size of the event history
+    #[instrument(skip(self))]
+    pub async fn get_event_history_info(&self) -> Result<EventHistoryInfo> {
+        self.get_history("api/v1/history/info").await
+    }
+
+    async fn get_history<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/{path}", self.network.stream_url.trim_end_matches('/'));
+        let response = self
+            .history_client
+            .get(
+                url.parse()
+                    .with_context(|| format!("invalid history url {url}"))?,
+            )
+            .await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if !status.is_success() {
+            bail!("history request failed with status {status}");
+        }
+
+        serde_json::from_slice(&body).context("failed to decode history response")
+    }
+}
+
+impl MatchmakerClient<LocalWallet> {
+    /// Constructs a client from a [`MatchmakerConfig`], e.g. one loaded with [`MatchmakerConfig::from_file`]
+    ///
+    /// * `config` - Network, auth key and request settings of the client
+    pub fn from_config(config: &MatchmakerConfig) -> Result<MatchmakerClient<LocalWallet>> {
+        let auth_signer = config
+            .auth_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("auth_key is not configured"))?
+            .load()?;
+
+        let mut http_builder = http_client::HttpClientBuilder::default();
+        if let Some(timeout) = config.request_timeout() {
+            http_builder = http_builder.request_timeout(timeout);
+        }
+
+        let mut client = Self::build(
+            auth_signer,
+            config.network()?,
+            EventClient::default(),
+            http_builder,
+            config.retry.clone(),
+            config.rate_limit.clone(),
+        )?;
+        client.default_privacy = config.default_privacy();
+        Ok(client)
+    }
 }
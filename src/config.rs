@@ -0,0 +1,383 @@
+//! Client configuration loaded from TOML/JSON files and environment variables
+//!
+//! Every field can be overridden with an environment variable prefixed with `MEV_SHARE_`, see
+//! [`MatchmakerConfig::with_env_overrides`].
+use crate::types::{HintPreference, MatchMakerNetwork, PrivacyParams, SupportedNetworks};
+use anyhow::{anyhow, bail, Context, Result};
+use ethers::signers::LocalWallet;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path, path::PathBuf, time::Duration};
+
+/// Prefix of the environment variables read by [`MatchmakerConfig::with_env_overrides`]
+pub const ENV_PREFIX: &str = "MEV_SHARE_";
+
+/// Where the key used to sign Flashbots authentication headers is read from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthKeySource {
+    /// Hex encoded private key
+    Key(String),
+    /// Name of an environment variable holding the hex encoded private key
+    Env(String),
+    /// Path of a file holding the hex encoded private key
+    File(PathBuf),
+}
+
+impl AuthKeySource {
+    /// Reads the key and parses it into a wallet
+    pub fn load(&self) -> Result<LocalWallet> {
+        let key = match self {
+            AuthKeySource::Key(key) => key.clone(),
+            AuthKeySource::Env(var) => {
+                std::env::var(var).with_context(|| format!("auth key variable {var} is not set"))?
+            }
+            AuthKeySource::File(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read auth key from {}", path.display()))?,
+        };
+
+        key.trim()
+            .trim_start_matches("0x")
+            .parse()
+            .context("invalid auth key")
+    }
+}
+
+/// Policy for retrying failed requests
+///
+/// Requests are retried on transport errors, HTTP `429` and `5xx` responses and the configured
+/// JSON-RPC error codes. Retries are sent with the exact same body, so the matchmaker dedupes a
+/// retried bundle instead of treating it as a new one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Maximum number of attempts per request, including the first one
+    pub max_attempts: u32,
+    /// Delay between attempts in milliseconds
+    pub backoff_ms: u64,
+    /// Time after the first attempt in milliseconds after which no more retries are started, for
+    /// requests without a deadline of their own, see [`crate::retry::with_deadline`]. Defaults to
+    /// one slot, after which the targeted block has usually been built.
+    pub deadline_ms: u64,
+    /// Time between two blocks in milliseconds. Bundles are retried until the last block they
+    /// target is due, `block_time_ms` for every block from `inclusion.block` to `max_block`.
+    pub block_time_ms: u64,
+    /// JSON-RPC error codes that are retried
+    pub retryable_codes: Vec<i64>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff_ms: 100,
+            deadline_ms: 12_000,
+            block_time_ms: 12_000,
+            // internal error & limit exceeded
+            retryable_codes: vec![-32603, -32005],
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay between attempts
+    pub fn backoff(&self) -> Duration {
+        Duration::from_millis(self.backoff_ms)
+    }
+
+    /// Time after the first attempt after which no more retries are started, unless the request has
+    /// its own deadline
+    pub fn deadline(&self) -> Duration {
+        Duration::from_millis(self.deadline_ms)
+    }
+
+    /// Time between two blocks
+    pub fn block_time(&self) -> Duration {
+        Duration::from_millis(self.block_time_ms)
+    }
+}
+
+/// Sustained rate and burst size of a token bucket
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateBudget {
+    /// Requests per second the bucket refills with
+    pub per_second: f64,
+    /// Maximum number of requests that can be sent at once
+    pub burst: u32,
+}
+
+impl RateBudget {
+    /// Checks that the bucket refills at a positive, finite rate and holds at least one request
+    pub fn validate(&self) -> Result<()> {
+        if !self.per_second.is_finite() || self.per_second <= 0.0 {
+            bail!(
+                "per_second must be a positive number, got {}",
+                self.per_second
+            );
+        }
+        if self.burst == 0 {
+            bail!("burst must be at least 1");
+        }
+        Ok(())
+    }
+}
+
+/// What happens to requests that exceed their rate budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottlePolicy {
+    /// Wait until the budget allows the request
+    #[default]
+    Queue,
+    /// Fail the request immediately with HTTP `429`
+    Drop,
+}
+
+/// Client-side rate limits of API requests. Requests are not limited unless a budget is configured.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Budget shared by all methods without their own budget
+    pub default: Option<RateBudget>,
+    /// Budgets of individual JSON-RPC methods, e.g. `mev_sendBundle`
+    pub methods: HashMap<String, RateBudget>,
+    /// What happens to requests that exceed their budget
+    pub policy: ThrottlePolicy,
+}
+
+impl RateLimitConfig {
+    /// Returns true if no budget is configured
+    pub fn is_unlimited(&self) -> bool {
+        self.default.is_none() && self.methods.is_empty()
+    }
+
+    /// Checks all budgets with [`RateBudget::validate`]
+    pub fn validate(&self) -> Result<()> {
+        if let Some(budget) = &self.default {
+            budget.validate().context("invalid default rate budget")?;
+        }
+        for (method, budget) in &self.methods {
+            budget
+                .validate()
+                .with_context(|| format!("invalid rate budget of {method}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of a [`MatchmakerClient`](crate::client::MatchmakerClient)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchmakerConfig {
+    /// Name of the network to connect to, e.g. "mainnet"
+    pub network: String,
+    /// Chain ID, required for networks that are not predefined
+    pub chain_id: Option<u64>,
+    /// Overrides the event stream URL of the network
+    pub stream_url: Option<String>,
+    /// Overrides the bundle & transaction API URL of the network
+    pub api_url: Option<String>,
+    /// Source of the Flashbots authentication key
+    pub auth_key: Option<AuthKeySource>,
+    /// Timeout of API requests in milliseconds
+    pub request_timeout_ms: Option<u64>,
+    /// Policy for retrying failed requests
+    pub retry: RetryConfig,
+    /// Client-side rate limits of requests
+    pub rate_limit: RateLimitConfig,
+    /// Builders bundles are sent to unless the bundle specifies its own
+    pub builders: Vec<String>,
+    /// Hints shared for bundles that do not specify their own
+    pub default_hints: Option<HintPreference>,
+}
+
+impl Default for MatchmakerConfig {
+    fn default() -> Self {
+        Self {
+            network: "mainnet".to_string(),
+            chain_id: None,
+            stream_url: None,
+            api_url: None,
+            auth_key: None,
+            request_timeout_ms: None,
+            retry: RetryConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            builders: Vec::new(),
+            default_hints: None,
+        }
+    }
+}
+
+impl MatchmakerConfig {
+    /// Loads the configuration from a `.toml` or `.json` file
+    ///
+    /// * `path` - Path of the configuration file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config from {}", path.display()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&contents),
+            Some("json") => Self::from_json(&contents),
+            _ => bail!("unsupported config format: {}", path.display()),
+        }
+    }
+
+    /// Parses the configuration from a TOML string
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let config: Self = toml::from_str(contents).context("invalid TOML config")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses the configuration from a JSON string
+    pub fn from_json(contents: &str) -> Result<Self> {
+        let config: Self = serde_json::from_str(contents).context("invalid JSON config")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks values that parse but can not be used, e.g. rate budgets that never refill
+    pub fn validate(&self) -> Result<()> {
+        self.rate_limit.validate()
+    }
+
+    /// Creates the default configuration with overrides from the environment applied
+    pub fn from_env() -> Result<Self> {
+        Self::default().with_env_overrides()
+    }
+
+    /// Overrides fields with the `MEV_SHARE_` prefixed environment variables that are set
+    ///
+    /// | Variable | Field |
+    /// | --- | --- |
+    /// | `MEV_SHARE_NETWORK` | `network` |
+    /// | `MEV_SHARE_CHAIN_ID` | `chain_id` |
+    /// | `MEV_SHARE_STREAM_URL` | `stream_url` |
+    /// | `MEV_SHARE_API_URL` | `api_url` |
+    /// | `MEV_SHARE_AUTH_KEY` | `auth_key`, as a hex encoded key |
+    /// | `MEV_SHARE_AUTH_KEY_FILE` | `auth_key`, as a key file |
+    /// | `MEV_SHARE_REQUEST_TIMEOUT_MS` | `request_timeout_ms` |
+    /// | `MEV_SHARE_RETRY_MAX_ATTEMPTS` | `retry.max_attempts` |
+    /// | `MEV_SHARE_RETRY_BACKOFF_MS` | `retry.backoff_ms` |
+    /// | `MEV_SHARE_RETRY_DEADLINE_MS` | `retry.deadline_ms` |
+    /// | `MEV_SHARE_RETRY_BLOCK_TIME_MS` | `retry.block_time_ms` |
+    /// | `MEV_SHARE_BUILDERS` | `builders`, comma separated |
+    pub fn with_env_overrides(self) -> Result<Self> {
+        self.with_overrides(|name| std::env::var(format!("{ENV_PREFIX}{name}")).ok())
+    }
+
+    /// Overrides fields with the values returned by `lookup`, see [`Self::with_env_overrides`].
+    ///
+    /// Empty values are treated as unset, so placeholders like `MEV_SHARE_AUTH_KEY=` keep the
+    /// configured value.
+    ///
+    /// * `lookup` - Returns the value of a variable, given its name without the `MEV_SHARE_` prefix
+    pub fn with_overrides<F>(mut self, lookup: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let lookup = |name: &str| lookup(name).filter(|value| !value.trim().is_empty());
+        if let Some(network) = lookup("NETWORK") {
+            self.network = network;
+        }
+        if let Some(chain_id) = lookup("CHAIN_ID") {
+            self.chain_id = Some(parse_var("CHAIN_ID", &chain_id)?);
+        }
+        if let Some(stream_url) = lookup("STREAM_URL") {
+            self.stream_url = Some(stream_url);
+        }
+        if let Some(api_url) = lookup("API_URL") {
+            self.api_url = Some(api_url);
+        }
+        if let Some(path) = lookup("AUTH_KEY_FILE") {
+            self.auth_key = Some(AuthKeySource::File(path.into()));
+        }
+        if let Some(key) = lookup("AUTH_KEY") {
+            self.auth_key = Some(AuthKeySource::Key(key));
+        }
+        if let Some(timeout) = lookup("REQUEST_TIMEOUT_MS") {
+            self.request_timeout_ms = Some(parse_var("REQUEST_TIMEOUT_MS", &timeout)?);
+        }
+        if let Some(attempts) = lookup("RETRY_MAX_ATTEMPTS") {
+            self.retry.max_attempts = parse_var("RETRY_MAX_ATTEMPTS", &attempts)?;
+        }
+        if let Some(backoff) = lookup("RETRY_BACKOFF_MS") {
+            self.retry.backoff_ms = parse_var("RETRY_BACKOFF_MS", &backoff)?;
+        }
+        if let Some(deadline) = lookup("RETRY_DEADLINE_MS") {
+            self.retry.deadline_ms = parse_var("RETRY_DEADLINE_MS", &deadline)?;
+        }
+        if let Some(block_time) = lookup("RETRY_BLOCK_TIME_MS") {
+            self.retry.block_time_ms = parse_var("RETRY_BLOCK_TIME_MS", &block_time)?;
+        }
+        if let Some(builders) = lookup("BUILDERS") {
+            self.builders = builders
+                .split(',')
+                .map(str::trim)
+                .filter(|builder| !builder.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        Ok(self)
+    }
+
+    /// Resolves the network to connect to from the predefined networks and the URL overrides
+    pub fn network(&self) -> Result<MatchMakerNetwork> {
+        let mut network = match SupportedNetworks::new().get_by_name(&self.network) {
+            Some(network) => network.clone(),
+            None => MatchMakerNetwork {
+                chain_id: self.chain_id.ok_or_else(|| {
+                    anyhow!("chain_id is required for custom network {}", self.network)
+                })?,
+                name: self.network.clone(),
+                stream_url: self.stream_url.clone().ok_or_else(|| {
+                    anyhow!("stream_url is required for custom network {}", self.network)
+                })?,
+                api_url: self.api_url.clone().ok_or_else(|| {
+                    anyhow!("api_url is required for custom network {}", self.network)
+                })?,
+            },
+        };
+
+        if let Some(chain_id) = self.chain_id {
+            network.chain_id = chain_id;
+        }
+        if let Some(stream_url) = &self.stream_url {
+            network.stream_url = stream_url.clone();
+        }
+        if let Some(api_url) = &self.api_url {
+            network.api_url = api_url.clone();
+        }
+
+        Ok(network)
+    }
+
+    /// Timeout of API requests, if configured
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Privacy parameters applied to bundles that do not specify their own, if any are configured
+    pub fn default_privacy(&self) -> Option<PrivacyParams> {
+        if self.builders.is_empty() && self.default_hints.is_none() {
+            return None;
+        }
+
+        Some(PrivacyParams {
+            hints: self.default_hints.clone(),
+            builders: self.builders.clone(),
+        })
+    }
+}
+
+fn parse_var<T>(name: &str, value: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value
+        .parse()
+        .with_context(|| format!("invalid value for {ENV_PREFIX}{name}: {value}"))
+}
@@ -0,0 +1,287 @@
+//! Typed views of common DeFi events in hint logs
+//!
+//! Recognizes Uniswap V2 `Sync` and `Swap`, Uniswap V3 `Swap`, Curve `TokenExchange`, Balancer V2
+//! `Swap` and ERC-20 `Transfer` logs. Hints often share the topics of a log but not its data, so
+//! [`LogKind::identify`] recognizes the event from its topics alone while [`decode_log`] also
+//! decodes the event's fields.
+use crate::types::{PendingBundle, PendingTransaction};
+use ethers::{
+    abi::RawLog,
+    contract::{EthEvent, EthLogDecode},
+    types::{Address, Bytes, H256, I256, U256},
+};
+use mev_share_rs::sse::EventTransactionLog;
+
+/// Uniswap V2 `Sync`, emitted with the new reserves after every change of a pair's balances
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Sync", abi = "Sync(uint112,uint112)")]
+pub struct UniswapV2Sync {
+    /// Reserve of token0
+    pub reserve0: u128,
+    /// Reserve of token1
+    pub reserve1: u128,
+}
+
+/// Uniswap V2 `Swap`
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Swap",
+    abi = "Swap(address,uint256,uint256,uint256,uint256,address)"
+)]
+pub struct UniswapV2Swap {
+    /// Caller of the pair, usually a router
+    #[ethevent(indexed)]
+    pub sender: Address,
+    /// Amount of token0 sent to the pair
+    pub amount0_in: U256,
+    /// Amount of token1 sent to the pair
+    pub amount1_in: U256,
+    /// Amount of token0 sent by the pair
+    pub amount0_out: U256,
+    /// Amount of token1 sent by the pair
+    pub amount1_out: U256,
+    /// Recipient of the output
+    #[ethevent(indexed)]
+    pub to: Address,
+}
+
+/// Uniswap V3 `Swap`. Amounts are the balance changes of the pool, positive amounts were sent to
+/// the pool.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Swap",
+    abi = "Swap(address,address,int256,int256,uint160,uint128,int24)"
+)]
+pub struct UniswapV3Swap {
+    /// Caller of the pool, usually a router
+    #[ethevent(indexed)]
+    pub sender: Address,
+    /// Recipient of the output
+    #[ethevent(indexed)]
+    pub recipient: Address,
+    /// Balance change of token0
+    pub amount0: I256,
+    /// Balance change of token1
+    pub amount1: I256,
+    /// Square root of the price after the swap, as a Q64.96
+    pub sqrt_price_x96: U256,
+    /// Liquidity in range after the swap
+    pub liquidity: u128,
+    /// Tick after the swap
+    pub tick: i32,
+}
+
+/// Curve `TokenExchange`
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "TokenExchange",
+    abi = "TokenExchange(address,int128,uint256,int128,uint256)"
+)]
+pub struct CurveTokenExchange {
+    /// Caller of the pool
+    #[ethevent(indexed)]
+    pub buyer: Address,
+    /// Index of the token sent to the pool
+    pub sold_id: i128,
+    /// Amount of the token sent to the pool
+    pub tokens_sold: U256,
+    /// Index of the token sent by the pool
+    pub bought_id: i128,
+    /// Amount of the token sent by the pool
+    pub tokens_bought: U256,
+}
+
+/// Balancer V2 `Swap`, emitted by the vault for swaps of all pools
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Swap", abi = "Swap(bytes32,address,address,uint256,uint256)")]
+pub struct BalancerSwap {
+    /// ID of the pool, starting with the pool's address
+    #[ethevent(indexed)]
+    pub pool_id: [u8; 32],
+    /// Token sent to the pool
+    #[ethevent(indexed)]
+    pub token_in: Address,
+    /// Token sent by the pool
+    #[ethevent(indexed)]
+    pub token_out: Address,
+    /// Amount of `token_in`
+    pub amount_in: U256,
+    /// Amount of `token_out`
+    pub amount_out: U256,
+}
+
+impl BalancerSwap {
+    /// Returns the address of the pool, encoded in the first 20 bytes of its ID
+    pub fn pool(&self) -> Address {
+        Address::from_slice(&self.pool_id[..20])
+    }
+}
+
+/// ERC-20 `Transfer`
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Transfer", abi = "Transfer(address,address,uint256)")]
+pub struct Erc20Transfer {
+    /// Sender of the tokens
+    #[ethevent(indexed)]
+    pub from: Address,
+    /// Recipient of the tokens
+    #[ethevent(indexed)]
+    pub to: Address,
+    /// Amount of tokens
+    pub value: U256,
+}
+
+/// Direction of a swap in a pool of two tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwapDirection {
+    /// token0 was sold for token1
+    ZeroForOne,
+    /// token1 was sold for token0
+    OneForZero,
+}
+
+impl UniswapV2Swap {
+    /// Returns the direction of the swap, `None` if both or no tokens were sent to the pair
+    pub fn direction(&self) -> Option<SwapDirection> {
+        match (self.amount0_in.is_zero(), self.amount1_in.is_zero()) {
+            (false, true) => Some(SwapDirection::ZeroForOne),
+            (true, false) => Some(SwapDirection::OneForZero),
+            _ => None,
+        }
+    }
+}
+
+impl UniswapV3Swap {
+    /// Returns the direction of the swap
+    pub fn direction(&self) -> SwapDirection {
+        if self.amount0 > I256::zero() {
+            SwapDirection::ZeroForOne
+        } else {
+            SwapDirection::OneForZero
+        }
+    }
+}
+
+/// The events recognized by this module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogKind {
+    /// [`UniswapV2Sync`]
+    UniswapV2Sync,
+    /// [`UniswapV2Swap`]
+    UniswapV2Swap,
+    /// [`UniswapV3Swap`]
+    UniswapV3Swap,
+    /// [`CurveTokenExchange`]
+    CurveTokenExchange,
+    /// [`BalancerSwap`]
+    BalancerSwap,
+    /// [`Erc20Transfer`]
+    Erc20Transfer,
+}
+
+impl LogKind {
+    /// Recognizes the event from the first topic of a log
+    ///
+    /// ERC-721 transfers share the signature of ERC-20 transfers and are identified as such, but
+    /// are not decoded by [`decode_raw_log`] as their token ID is indexed.
+    ///
+    /// * `topics` - Topics of the log, the first one being the event signature
+    pub fn identify(topics: &[H256]) -> Option<LogKind> {
+        let topic = *topics.first()?;
+        [
+            (UniswapV2Sync::signature(), LogKind::UniswapV2Sync),
+            (UniswapV2Swap::signature(), LogKind::UniswapV2Swap),
+            (UniswapV3Swap::signature(), LogKind::UniswapV3Swap),
+            (CurveTokenExchange::signature(), LogKind::CurveTokenExchange),
+            (BalancerSwap::signature(), LogKind::BalancerSwap),
+            (Erc20Transfer::signature(), LogKind::Erc20Transfer),
+        ]
+        .into_iter()
+        .find_map(|(signature, kind)| (signature == topic).then_some(kind))
+    }
+}
+
+/// A decoded event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedEvent {
+    UniswapV2Sync(UniswapV2Sync),
+    UniswapV2Swap(UniswapV2Swap),
+    UniswapV3Swap(UniswapV3Swap),
+    CurveTokenExchange(CurveTokenExchange),
+    BalancerSwap(BalancerSwap),
+    Erc20Transfer(Erc20Transfer),
+}
+
+/// A decoded log and the contract that emitted it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedLog {
+    /// Contract that emitted the log, the pool for swaps except Balancer's, or the token for
+    /// transfers
+    pub address: Address,
+    /// The decoded event
+    pub event: DecodedEvent,
+}
+
+impl DecodedLog {
+    /// Returns the pool the event is about, `None` for transfers
+    pub fn pool(&self) -> Option<Address> {
+        match &self.event {
+            DecodedEvent::BalancerSwap(swap) => Some(swap.pool()),
+            DecodedEvent::Erc20Transfer(_) => None,
+            _ => Some(self.address),
+        }
+    }
+}
+
+/// Decodes a log of a recognized event, see [`decode_raw_log`]
+pub fn decode_log(log: &EventTransactionLog) -> Option<DecodedLog> {
+    decode_raw_log(log.address, &log.topics, &log.data)
+}
+
+/// Decodes the logs of recognized events, skipping all other logs and logs without data
+pub fn decode_logs<'a>(logs: impl IntoIterator<Item = &'a EventTransactionLog>) -> Vec<DecodedLog> {
+    logs.into_iter().filter_map(decode_log).collect()
+}
+
+/// Decodes a log of a recognized event
+///
+/// Returns `None` if the event is not recognized or the log's topics and data do not match the
+/// event, e.g. because the hint did not share the data.
+///
+/// * `address` - Contract that emitted the log
+/// * `topics` - Topics of the log
+/// * `data` - Data of the log
+pub fn decode_raw_log(address: Address, topics: &[H256], data: &Bytes) -> Option<DecodedLog> {
+    let raw = RawLog {
+        topics: topics.to_vec(),
+        data: data.to_vec(),
+    };
+    let event = match LogKind::identify(topics)? {
+        LogKind::UniswapV2Sync => DecodedEvent::UniswapV2Sync(decode(&raw)?),
+        LogKind::UniswapV2Swap => DecodedEvent::UniswapV2Swap(decode(&raw)?),
+        LogKind::UniswapV3Swap => DecodedEvent::UniswapV3Swap(decode(&raw)?),
+        LogKind::CurveTokenExchange => DecodedEvent::CurveTokenExchange(decode(&raw)?),
+        LogKind::BalancerSwap => DecodedEvent::BalancerSwap(decode(&raw)?),
+        LogKind::Erc20Transfer => DecodedEvent::Erc20Transfer(decode(&raw)?),
+    };
+
+    Some(DecodedLog { address, event })
+}
+
+impl PendingTransaction {
+    /// Decodes the logs of recognized events shared in the hint, see [`decode_logs`]
+    pub fn decoded_logs(&self) -> Vec<DecodedLog> {
+        decode_logs(self.logs.iter().flatten())
+    }
+}
+
+impl PendingBundle {
+    /// Decodes the logs of recognized events shared in the hint, see [`decode_logs`]
+    pub fn decoded_logs(&self) -> Vec<DecodedLog> {
+        decode_logs(self.logs.iter().flatten())
+    }
+}
+
+fn decode<T: EthLogDecode>(raw: &RawLog) -> Option<T> {
+    T::decode_log(raw).ok()
+}
@@ -0,0 +1,311 @@
+//! Submission of the same bundle to several relays and builders in parallel
+//!
+//! The `mev_sendBundle` request is serialized and signed once, every endpoint receives the
+//! identical body and `X-Flashbots-Signature` header.
+use crate::{
+    jsonrpc, metrics,
+    signer_middleware::{sign_body, SIGNATURE_HEADER},
+    types::{Bundle, MatchMakerNetwork, SendBundleResponse},
+};
+use anyhow::Result;
+use ethers::{signers::Signer, types::H256};
+use futures_util::future::join_all;
+use http::{header::HeaderValue, Request};
+use hyper::{client::HttpConnector, Body, Client};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use serde::Deserialize;
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{debug, instrument, warn};
+
+/// HTTP client for requests sent outside of the JSON-RPC client, to `http` or `https` URLs
+pub(crate) type HttpsClient = Client<HttpsConnector<HttpConnector>>;
+
+/// Creates an [`HttpsClient`] verifying certificates against the webpki roots
+pub(crate) fn https_client() -> HttpsClient {
+    let connector = HttpsConnectorBuilder::new()
+        .with_webpki_roots()
+        .https_or_http()
+        .enable_http1()
+        .build();
+    Client::builder().build(connector)
+}
+
+/// A relay or builder endpoint accepting `mev_sendBundle`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// Name used in logs and responses, e.g. "flashbots"
+    pub name: String,
+    /// URL of the JSON-RPC API
+    pub url: String,
+}
+
+impl Endpoint {
+    /// Creates an endpoint
+    ///
+    /// * `name` - Name used in logs and responses
+    /// * `url` - URL of the JSON-RPC API
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+        }
+    }
+}
+
+impl From<&MatchMakerNetwork> for Endpoint {
+    fn from(network: &MatchMakerNetwork) -> Self {
+        Endpoint::new(network.name.clone(), network.api_url.clone())
+    }
+}
+
+/// Reasons a submission to a single endpoint failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FanoutError {
+    /// The request could not be sent or the response not be read
+    Transport(String),
+    /// The request did not complete within the submitter's timeout
+    Timeout,
+    /// The endpoint responded with a non-success HTTP status
+    Status(u16),
+    /// The endpoint responded with a JSON-RPC error
+    Rpc { code: i64, message: String },
+    /// The response is not a valid `mev_sendBundle` response
+    Decode(String),
+}
+
+impl fmt::Display for FanoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FanoutError::Transport(err) => write!(f, "transport error: {err}"),
+            FanoutError::Timeout => write!(f, "request timed out"),
+            FanoutError::Status(status) => write!(f, "HTTP status {status}"),
+            FanoutError::Rpc { code, message } => write!(f, "JSON-RPC error {code}: {message}"),
+            FanoutError::Decode(err) => write!(f, "invalid response: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FanoutError {}
+
+/// Response of a single endpoint
+#[derive(Debug, Clone)]
+pub struct EndpointResponse {
+    /// The endpoint the bundle was sent to
+    pub endpoint: Endpoint,
+    /// The endpoint's response or the reason the submission failed
+    pub result: Result<SendBundleResponse, FanoutError>,
+}
+
+/// Aggregated responses of all endpoints, in the order the endpoints were configured
+#[derive(Debug, Clone)]
+pub struct FanoutResponse {
+    /// Per-endpoint responses
+    pub responses: Vec<EndpointResponse>,
+}
+
+impl FanoutResponse {
+    /// Returns the endpoints that accepted the bundle with their responses
+    pub fn successes(&self) -> impl Iterator<Item = (&Endpoint, &SendBundleResponse)> {
+        self.responses.iter().filter_map(|response| {
+            response
+                .result
+                .as_ref()
+                .ok()
+                .map(|result| (&response.endpoint, result))
+        })
+    }
+
+    /// Returns the endpoints that failed with their errors
+    pub fn errors(&self) -> impl Iterator<Item = (&Endpoint, &FanoutError)> {
+        self.responses.iter().filter_map(|response| {
+            response
+                .result
+                .as_ref()
+                .err()
+                .map(|err| (&response.endpoint, err))
+        })
+    }
+
+    /// Returns true if at least one endpoint accepted the bundle
+    pub fn is_any_success(&self) -> bool {
+        self.successes().next().is_some()
+    }
+
+    /// Returns the bundle hash reported by the first endpoint that accepted the bundle
+    pub fn bundle_hash(&self) -> Option<H256> {
+        self.successes()
+            .map(|(_, result)| result.bundle_hash)
+            .next()
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<SendBundleResponse>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    #[serde(default)]
+    message: String,
+}
+
+/// Submits bundles to multiple endpoints in parallel, see the [module docs](self)
+///
+/// The submitter is cheap to clone, clones share the underlying HTTP client.
+#[derive(Clone)]
+pub struct FanoutSubmitter<S> {
+    auth_signer: Arc<S>,
+    endpoints: Arc<Vec<Endpoint>>,
+    http_client: HttpsClient,
+    timeout: Option<Duration>,
+    validate_bundles: bool,
+}
+
+impl<S> FanoutSubmitter<S>
+where
+    S: Signer + 'static,
+{
+    /// Constructs a submitter sending to `endpoints`
+    ///
+    /// * `auth_signer` - Signer of the Flashbots authentication header
+    /// * `endpoints` - Relays and builders bundles are sent to
+    pub fn new(
+        auth_signer: S,
+        endpoints: impl IntoIterator<Item = Endpoint>,
+    ) -> FanoutSubmitter<S> {
+        Self {
+            auth_signer: Arc::new(auth_signer),
+            endpoints: Arc::new(endpoints.into_iter().collect()),
+            http_client: https_client(),
+            timeout: None,
+            validate_bundles: true,
+        }
+    }
+
+    /// Sets the timeout of the request to each endpoint
+    ///
+    /// * `timeout` - Time after which an endpoint's submission fails with [`FanoutError::Timeout`]
+    pub fn with_timeout(mut self, timeout: Duration) -> FanoutSubmitter<S> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Enables or disables client-side validation of bundles in [`Self::send_bundle`]. Enabled by default.
+    ///
+    /// * `enabled` - Whether bundles are validated before being sent
+    pub fn with_bundle_validation(mut self, enabled: bool) -> FanoutSubmitter<S> {
+        self.validate_bundles = enabled;
+        self
+    }
+
+    /// Returns the endpoints bundles are sent to
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// Sends a bundle to all endpoints in parallel
+    ///
+    /// Fails if the bundle is invalid or cannot be signed, failures of individual endpoints are
+    /// reported in the [`FanoutResponse`].
+    ///
+    /// * `bundle` - Params for the bundle to be sent
+    #[instrument(skip_all, fields(bundle_hash = ?bundle.hash(), endpoints = self.endpoints.len()))]
+    pub async fn send_bundle(&self, bundle: &Bundle) -> Result<FanoutResponse> {
+        if self.validate_bundles {
+            bundle.validate()?;
+        }
+
+        let body = serde_json::to_vec(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "mev_sendBundle",
+            "params": [bundle],
+        }))?;
+        let signature = sign_body(self.auth_signer.as_ref(), &body)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to sign request: {err}"))?;
+
+        let responses = join_all(self.endpoints.iter().map(|endpoint| {
+            let body = body.clone();
+            let signature = signature.clone();
+            async move {
+                let start = Instant::now();
+                let result = match self.timeout {
+                    Some(timeout) => {
+                        tokio::time::timeout(timeout, self.submit(endpoint, body, signature))
+                            .await
+                            .unwrap_or(Err(FanoutError::Timeout))
+                    }
+                    None => self.submit(endpoint, body, signature).await,
+                };
+
+                let status = match &result {
+                    Ok(_) => "ok".to_string(),
+                    Err(FanoutError::Rpc { code, .. }) => code.to_string(),
+                    Err(FanoutError::Status(status)) => status.to_string(),
+                    Err(_) => "transport".to_string(),
+                };
+                metrics::record_request("mev_sendBundle", &status, start.elapsed());
+                match &result {
+                    Ok(_) => debug!(endpoint = endpoint.name, "Sent bundle"),
+                    Err(err) => {
+                        warn!(endpoint = endpoint.name, error = %err, "Failed to send bundle")
+                    }
+                }
+
+                EndpointResponse {
+                    endpoint: endpoint.clone(),
+                    result,
+                }
+            }
+        }))
+        .await;
+
+        Ok(FanoutResponse { responses })
+    }
+
+    async fn submit(
+        &self,
+        endpoint: &Endpoint,
+        body: Vec<u8>,
+        signature: HeaderValue,
+    ) -> Result<SendBundleResponse, FanoutError> {
+        let request = Request::post(&endpoint.url)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(Body::from(body))
+            .map_err(|err| FanoutError::Transport(err.to_string()))?;
+
+        let response = self
+            .http_client
+            .request(request)
+            .await
+            .map_err(|err| FanoutError::Transport(err.to_string()))?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|err| FanoutError::Transport(err.to_string()))?;
+
+        if !status.is_success() && jsonrpc::response_error_code(&body).is_none() {
+            return Err(FanoutError::Status(status.as_u16()));
+        }
+
+        let response: RpcResponse =
+            serde_json::from_slice(&body).map_err(|err| FanoutError::Decode(err.to_string()))?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(FanoutError::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(FanoutError::Decode("missing result".to_string())),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Typed loader for the MEV-Share spec test vectors
+//!
+//! The vectors in `tests/tests.json` describe a genesis state, the header of the block bundles are
+//! built on and a list of bundles with their expected outcome. [`SpecFixture::spec`] returns the
+//! vectors bundled with the crate, other files in the same format are loaded with
+//! [`SpecFixture::from_file`].
+use crate::types::Bundle;
+use anyhow::{Context, Result};
+use ethers::types::{Address, Bytes, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+/// The spec test vectors bundled with the crate
+const SPEC_TESTS: &str = include_str!("../tests/tests.json");
+
+/// A set of spec test vectors sharing a genesis state and block header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecFixture {
+    /// Accounts of the genesis state, keyed by address
+    pub genesis_alloc: HashMap<Address, GenesisAccount>,
+    /// Header of the block the bundles are built on
+    pub header: SpecHeader,
+    /// The test vectors
+    pub tests: Vec<SpecTest>,
+}
+
+/// An account of the genesis state
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisAccount {
+    /// Nonce of the account, 0 if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    /// Balance of the account, in wei
+    pub balance: U256,
+    /// Code of the account, if it is a contract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Storage of the account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<HashMap<H256, H256>>,
+}
+
+/// The fields of the block header the vectors are simulated on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecHeader {
+    /// Hash of the block
+    pub hash: H256,
+    /// Hash of the parent block
+    pub parent_hash: H256,
+    /// Coinbase of the block
+    pub miner: Address,
+    /// Number of the block
+    pub number: U64,
+    /// Gas limit of the block
+    pub gas_limit: U256,
+    /// Timestamp of the block, in seconds since the epoch
+    pub timestamp: U256,
+    /// Base fee of the block, in wei
+    #[serde(default)]
+    pub base_fee_per_gas: Option<U256>,
+}
+
+/// A single test vector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecTest {
+    /// Name of the vector
+    pub name: String,
+    /// The bundle under test
+    pub bundle: Bundle,
+    /// Whether the matchmaker must reject or fail to simulate the bundle
+    pub should_fail: bool,
+    /// Refunds the matchmaker extracts for the bundle, innermost bundles first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extracted_refunds: Vec<ExtractedRefund>,
+}
+
+/// A refund extracted for a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedRefund {
+    /// Refunded value, in wei
+    pub value: U256,
+    /// Percentage of the backrun's profit that is refunded
+    pub percent: u32,
+    /// Recipients of the refund and their percentage of it
+    pub refund_split: BTreeMap<Address, u32>,
+}
+
+impl SpecFixture {
+    /// Returns the spec test vectors bundled with the crate
+    pub fn spec() -> Self {
+        Self::from_json(SPEC_TESTS).expect("bundled spec tests are valid")
+    }
+
+    /// Loads test vectors from a JSON file
+    ///
+    /// * `path` - Path of a file in the format of `tests/tests.json`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read fixture from {}", path.display()))?;
+        Self::from_json(&contents)
+    }
+
+    /// Parses test vectors from a JSON string
+    pub fn from_json(contents: &str) -> Result<Self> {
+        serde_json::from_str(contents).context("invalid spec fixture")
+    }
+
+    /// Returns the test vector with the given name
+    pub fn test(&self, name: &str) -> Option<&SpecTest> {
+        self.tests.iter().find(|test| test.name == name)
+    }
+
+    /// Returns the nonce of `address` in the genesis state
+    pub fn genesis_nonce(&self, address: &Address) -> U64 {
+        self.genesis_alloc
+            .get(address)
+            .and_then(|account| account.nonce)
+            .unwrap_or_default()
+    }
+}
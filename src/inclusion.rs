@@ -0,0 +1,149 @@
+//! Tracking whether a sent bundle landed on chain
+//!
+//! The [`InclusionTracker`] checks every block in the bundle's inclusion range for the bundle's
+//! transactions. A bundle is included if all of its transactions appear back to back and in bundle
+//! order. If only some of them appear, e.g. the backrun target without our backrun, the bundle was
+//! outbid.
+use crate::{
+    blocks::{BlockContents, BlockSource},
+    types::{Bundle, BundleTx},
+};
+use ethers::types::{TxHash, U64};
+use futures_util::StreamExt;
+use tracing::{debug, info, instrument, warn};
+
+/// Outcome of tracking a bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InclusionStatus {
+    /// All transactions of the bundle were included in order
+    Included {
+        /// Block the bundle was included in
+        block: U64,
+        /// Index of the bundle's first transaction in the block
+        position: usize,
+    },
+    /// None of the bundle's transactions were included up to its `max_block`
+    Expired,
+    /// Some, but not all, of the bundle's transactions were included, or not in bundle order
+    Outbid {
+        /// Block the transactions were included in
+        block: U64,
+    },
+}
+
+/// Watches blocks for the transactions of a bundle, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct InclusionTracker {
+    tx_hashes: Vec<TxHash>,
+    block: U64,
+    max_block: U64,
+}
+
+impl InclusionTracker {
+    /// Creates a tracker for transactions that are expected to land together
+    ///
+    /// * `tx_hashes` - Hashes of the bundle's transactions, in bundle order
+    /// * `block` - First block the bundle can be included in
+    /// * `max_block` - Last block the bundle can be included in
+    pub fn new(tx_hashes: Vec<TxHash>, block: U64, max_block: U64) -> Self {
+        Self {
+            tx_hashes,
+            block,
+            max_block,
+        }
+    }
+
+    /// Creates a tracker for all transactions of `bundle`, including those of nested bundles,
+    /// within its inclusion range. `max_block` defaults to the target block if unset.
+    pub fn for_bundle(bundle: &Bundle) -> Self {
+        Self::new(
+            bundle_tx_hashes(bundle),
+            bundle.inclusion.block,
+            bundle.inclusion.max_block.unwrap_or(bundle.inclusion.block),
+        )
+    }
+
+    /// Returns the tracked transaction hashes, in bundle order
+    pub fn tx_hashes(&self) -> &[TxHash] {
+        &self.tx_hashes
+    }
+
+    /// Checks the transactions of a single block
+    ///
+    /// Returns `None` if none of the tracked transactions are in the block.
+    ///
+    /// * `block` - Number of the block
+    /// * `transactions` - Hashes of the block's transactions, in block order
+    pub fn check_block(&self, block: U64, transactions: &[TxHash]) -> Option<InclusionStatus> {
+        if self.tx_hashes.is_empty() {
+            return None;
+        }
+
+        if let Some(position) = transactions
+            .windows(self.tx_hashes.len())
+            .position(|window| window == self.tx_hashes.as_slice())
+        {
+            return Some(InclusionStatus::Included { block, position });
+        }
+
+        transactions
+            .iter()
+            .any(|tx| self.tx_hashes.contains(tx))
+            .then_some(InclusionStatus::Outbid { block })
+    }
+
+    /// Checks every block from the bundle's target block up to its `max_block` as they are
+    /// produced by `source`.
+    ///
+    /// Returns `None` if `source` ends before the bundle landed or expired.
+    ///
+    /// * `source` - Source of new block numbers and their transactions
+    #[instrument(skip_all, fields(block = %self.block, max_block = %self.max_block))]
+    pub async fn track<B>(&self, source: &B) -> Option<InclusionStatus>
+    where
+        B: BlockSource + BlockContents,
+    {
+        let mut next = self.block;
+        let mut block_numbers = source.block_numbers();
+
+        while let Some(latest) = block_numbers.next().await {
+            while next <= latest.min(self.max_block) {
+                let transactions = match source.block_transactions(next).await {
+                    Ok(Some(transactions)) => transactions,
+                    Ok(None) => {
+                        debug!(block = %next, "Block not available yet");
+                        break;
+                    }
+                    Err(err) => {
+                        warn!(block = %next, error = %err, "Failed to get block transactions");
+                        break;
+                    }
+                };
+
+                if let Some(status) = self.check_block(next, &transactions) {
+                    info!(?status, "Bundle resolved");
+                    return Some(status);
+                }
+                next += U64::one();
+            }
+
+            if next > self.max_block {
+                info!("Bundle expired");
+                return Some(InclusionStatus::Expired);
+            }
+        }
+
+        None
+    }
+}
+
+fn bundle_tx_hashes(bundle: &Bundle) -> Vec<TxHash> {
+    bundle
+        .body
+        .iter()
+        .flat_map(|tx| match tx {
+            BundleTx::Bundle { bundle } => bundle_tx_hashes(bundle),
+            tx => vec![tx.hash()],
+        })
+        .collect()
+}
@@ -0,0 +1,52 @@
+//! Helpers for inspecting raw JSON-RPC request and response bodies in the HTTP middleware
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: serde_json::Value,
+    method: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+}
+
+/// Returns the method of a single JSON-RPC request, `None` for batches or malformed bodies
+pub(crate) fn request_method(body: &[u8]) -> Option<String> {
+    serde_json::from_slice::<RpcRequest>(body)
+        .ok()
+        .map(|request| request.method)
+}
+
+/// Returns the id of a single JSON-RPC request, `None` for batches or malformed bodies
+pub(crate) fn request_id(body: &[u8]) -> Option<serde_json::Value> {
+    serde_json::from_slice::<RpcRequest>(body)
+        .ok()
+        .map(|request| request.id)
+}
+
+/// Builds the body of a JSON-RPC error response
+pub(crate) fn error_response(id: serde_json::Value, code: i64, message: &str) -> Vec<u8> {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Returns the error code of a single JSON-RPC response, `None` for successful or malformed responses
+pub(crate) fn response_error_code(body: &[u8]) -> Option<i64> {
+    serde_json::from_slice::<RpcResponse>(body)
+        .ok()
+        .and_then(|response| response.error)
+        .map(|error| error.code)
+}
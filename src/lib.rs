@@ -1,3 +1,22 @@
+pub mod blocks;
 pub mod client;
+pub mod config;
+#[cfg(feature = "decoders")]
+pub mod decoders;
+pub mod fanout;
+pub mod fixtures;
+pub mod inclusion;
+mod jsonrpc;
+pub mod metrics;
+pub mod nonce_manager;
+pub mod rate_limit;
+pub mod retry;
+pub mod scheduler;
+pub mod selectors;
 pub mod signer_middleware;
+pub mod strategy;
+#[cfg(feature = "subscriber")]
+pub mod telemetry;
+pub mod transaction;
 pub mod types;
+pub mod validation;
@@ -0,0 +1,291 @@
+//! Prometheus metrics for the event stream and API requests
+//!
+//! Metrics are only collected with the `metrics` feature enabled, without it recording is a no-op.
+//! Use [`render`] to expose them in the Prometheus text format, or [`registry`] to merge them into
+//! an application's own registry.
+use crate::jsonrpc;
+use futures_util::future::BoxFuture;
+use http::Request;
+use hyper::{Body, Response};
+use std::{
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+
+#[cfg(feature = "metrics")]
+use prometheus::{
+    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+#[cfg(feature = "metrics")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "metrics")]
+struct Metrics {
+    registry: Registry,
+    events: IntCounterVec,
+    decode_errors: IntCounter,
+    stream_connects: IntCounterVec,
+    stream_reconnects: IntCounter,
+    callback_duration: Histogram,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    signing_duration: Histogram,
+    throttled: IntCounterVec,
+    throttle_wait: Histogram,
+}
+
+#[cfg(feature = "metrics")]
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("mev_share".to_string()), None)?;
+
+        let events = IntCounterVec::new(
+            Opts::new("events_total", "Events received from the event stream"),
+            &["event_type"],
+        )?;
+        let decode_errors = IntCounter::new(
+            "event_decode_errors_total",
+            "Events from the event stream that failed to decode",
+        )?;
+        let stream_connects = IntCounterVec::new(
+            Opts::new(
+                "stream_connects_total",
+                "Connection attempts to the event stream",
+            ),
+            &["status"],
+        )?;
+        let stream_reconnects = IntCounter::new(
+            "stream_reconnects_total",
+            "Reconnects of the event stream after a connection or retry error",
+        )?;
+        let callback_duration = Histogram::with_opts(HistogramOpts::new(
+            "callback_duration_seconds",
+            "Time spent in event callbacks",
+        ))?;
+        let requests = IntCounterVec::new(
+            Opts::new("requests_total", "API requests by method and outcome"),
+            &["method", "status"],
+        )?;
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new("request_duration_seconds", "Latency of API requests"),
+            &["method"],
+        )?;
+        let signing_duration = Histogram::with_opts(HistogramOpts::new(
+            "signing_duration_seconds",
+            "Time spent signing Flashbots authentication headers",
+        ))?;
+        let throttled = IntCounterVec::new(
+            Opts::new(
+                "throttled_requests_total",
+                "API requests that exceeded their client-side rate budget",
+            ),
+            &["method", "policy"],
+        )?;
+        let throttle_wait = Histogram::with_opts(HistogramOpts::new(
+            "throttle_wait_seconds",
+            "Time queued requests waited for their rate budget",
+        ))?;
+
+        registry.register(Box::new(events.clone()))?;
+        registry.register(Box::new(decode_errors.clone()))?;
+        registry.register(Box::new(stream_connects.clone()))?;
+        registry.register(Box::new(stream_reconnects.clone()))?;
+        registry.register(Box::new(callback_duration.clone()))?;
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+        registry.register(Box::new(signing_duration.clone()))?;
+        registry.register(Box::new(throttled.clone()))?;
+        registry.register(Box::new(throttle_wait.clone()))?;
+
+        Ok(Self {
+            registry,
+            events,
+            decode_errors,
+            stream_connects,
+            stream_reconnects,
+            callback_duration,
+            requests,
+            request_duration,
+            signing_duration,
+            throttled,
+            throttle_wait,
+        })
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| Metrics::new().expect("metrics are valid"))
+}
+
+/// Returns the registry holding the client's metrics
+#[cfg(feature = "metrics")]
+pub fn registry() -> &'static Registry {
+    &metrics().registry
+}
+
+/// Renders the client's metrics in the Prometheus text exposition format
+#[cfg(feature = "metrics")]
+pub fn render() -> String {
+    TextEncoder::new()
+        .encode_to_string(&registry().gather())
+        .unwrap_or_default()
+}
+
+/// Records an event received from the event stream
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_event(event_type: &str) {
+    #[cfg(feature = "metrics")]
+    metrics().events.with_label_values(&[event_type]).inc();
+}
+
+/// Records an event that failed to decode
+pub(crate) fn record_decode_error() {
+    #[cfg(feature = "metrics")]
+    metrics().decode_errors.inc();
+}
+
+/// Records a connection attempt to the event stream
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_stream_connect(connected: bool) {
+    #[cfg(feature = "metrics")]
+    metrics()
+        .stream_connects
+        .with_label_values(&[if connected { "ok" } else { "error" }])
+        .inc();
+}
+
+/// Records a reconnect of the event stream after it failed
+pub(crate) fn record_stream_reconnect() {
+    #[cfg(feature = "metrics")]
+    metrics().stream_reconnects.inc();
+}
+
+/// Records the time spent in an event callback
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn observe_callback(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics().callback_duration.observe(duration.as_secs_f64());
+}
+
+/// Records the time spent signing a request
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn observe_signing(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    metrics().signing_duration.observe(duration.as_secs_f64());
+}
+
+/// Records an API request. `status` is `ok`, the JSON-RPC error code or the kind of failure.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_request(method: &str, status: &str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        let metrics = metrics();
+        metrics.requests.with_label_values(&[method, status]).inc();
+        metrics
+            .request_duration
+            .with_label_values(&[method])
+            .observe(duration.as_secs_f64());
+    }
+}
+
+/// Records a request that exceeded its rate budget. `wait` is the time it was queued for.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_throttled(method: &str, policy: &str, wait: Option<Duration>) {
+    #[cfg(feature = "metrics")]
+    {
+        let metrics = metrics();
+        metrics.throttled.with_label_values(&[method, policy]).inc();
+        if let Some(wait) = wait {
+            metrics.throttle_wait.observe(wait.as_secs_f64());
+        }
+    }
+}
+
+/// Layer that applies [`RequestMetrics`], which records the method, outcome and latency of requests
+#[derive(Clone, Default)]
+pub(crate) struct RequestMetricsLayer;
+
+impl<I> Layer<I> for RequestMetricsLayer {
+    type Service = RequestMetrics<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        RequestMetrics { inner }
+    }
+}
+
+/// Middleware that records the method, outcome and latency of JSON-RPC requests.
+///
+/// Passes requests through untouched when the `metrics` feature is disabled.
+#[derive(Clone)]
+pub struct RequestMetrics<I> {
+    inner: I,
+}
+
+impl<I> Service<Request<Body>> for RequestMetrics<I>
+where
+    I: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    I::Future: Send,
+    I::Error: From<hyper::Error>,
+{
+    type Response = I::Response;
+    type Error = I::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        // wait for service to be ready
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        if cfg!(not(feature = "metrics")) {
+            return Box::pin(inner.call(request));
+        }
+
+        let (parts, body) = request.into_parts();
+
+        Box::pin(async move {
+            let body_bytes = hyper::body::to_bytes(body).await?;
+            let method =
+                jsonrpc::request_method(&body_bytes).unwrap_or_else(|| "batch".to_string());
+
+            let start = Instant::now();
+            let response = match inner
+                .call(Request::from_parts(parts, Body::from(body_bytes)))
+                .await
+            {
+                Ok(response) => response,
+                Err(err) => {
+                    record_request(&method, "transport", start.elapsed());
+                    return Err(err);
+                }
+            };
+
+            let (parts, body) = response.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body_bytes) => {
+                    let status = match jsonrpc::response_error_code(&body_bytes) {
+                        Some(code) => code.to_string(),
+                        None if parts.status.is_success() => "ok".to_string(),
+                        None => parts.status.as_str().to_string(),
+                    };
+                    record_request(&method, &status, start.elapsed());
+                    Body::from(body_bytes)
+                }
+                Err(err) => {
+                    record_request(&method, "transport", start.elapsed());
+                    Body::wrap_stream(futures_util::stream::once(async move {
+                        Err::<hyper::body::Bytes, _>(err)
+                    }))
+                }
+            };
+
+            Ok(Response::from_parts(parts, body))
+        })
+    }
+}
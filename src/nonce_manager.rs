@@ -0,0 +1,191 @@
+//! Nonce tracking for searcher accounts that submit bundles concurrently
+//!
+//! Bundles are not visible in the public mempool, so the provider's transaction count does not
+//! account for bundles that are still pending. The [`NonceManager`] hands out nonces on top of the
+//! last synced count and keeps them reserved until the bundle is included or expires.
+use crate::types::{Bundle, MatchMakerNetwork};
+use ethers::{
+    providers::Middleware,
+    signers::Signer,
+    types::{transaction::eip2718::TypedTransaction, Address, BlockId, BlockNumber, U256, U64},
+};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+
+/// A nonce handed out by the [`NonceManager`] for a bundle transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceReservation {
+    /// Account the nonce belongs to
+    pub address: Address,
+    /// The reserved nonce
+    pub nonce: U256,
+    /// Last block the bundle using this nonce can be included in
+    pub max_block: U64,
+}
+
+#[derive(Debug, Default)]
+struct AccountNonces {
+    /// Next nonce not yet used on chain
+    next: U256,
+    /// Reserved nonces mapped to the last block they are valid for
+    reserved: BTreeMap<U256, U64>,
+}
+
+impl AccountNonces {
+    fn lowest_free(&self) -> U256 {
+        let mut nonce = self.next;
+        for reserved in self.reserved.keys() {
+            if *reserved != nonce {
+                break;
+            }
+            nonce += U256::one();
+        }
+        nonce
+    }
+
+    fn advance_to(&mut self, next: U256) {
+        self.next = next;
+        self.reserved = self.reserved.split_off(&next);
+    }
+}
+
+/// Tracks pending nonces per account across concurrent bundle submissions
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    accounts: Mutex<HashMap<Address, AccountNonces>>,
+}
+
+impl NonceManager {
+    /// Creates an empty nonce manager. Accounts start at nonce `0` until synced.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the next on-chain nonce of `address` from the provider's transaction count at the latest block.
+    ///
+    /// Reservations below the synced nonce are dropped, since those nonces have been used.
+    ///
+    /// * `provider` - Provider to read the transaction count from
+    /// * `address` - Account to sync
+    pub async fn sync<M: Middleware>(
+        &self,
+        provider: &M,
+        address: Address,
+    ) -> Result<U256, M::Error> {
+        let next = provider
+            .get_transaction_count(address, Some(BlockId::Number(BlockNumber::Latest)))
+            .await?;
+        self.set_next(address, next);
+        Ok(next)
+    }
+
+    /// Sets the next on-chain nonce of `address`, dropping reservations below it.
+    ///
+    /// * `address` - Account to update
+    /// * `next` - Next nonce not yet used on chain
+    pub fn set_next(&self, address: Address, next: U256) {
+        self.accounts
+            .lock()
+            .entry(address)
+            .or_default()
+            .advance_to(next);
+    }
+
+    /// Reserves the lowest nonce of `address` that is neither used on chain nor reserved.
+    ///
+    /// * `address` - Account to reserve a nonce for
+    /// * `max_block` - Last block the bundle using the nonce can be included in
+    pub fn reserve(&self, address: Address, max_block: U64) -> NonceReservation {
+        let mut accounts = self.accounts.lock();
+        let account = accounts.entry(address).or_default();
+        let nonce = account.lowest_free();
+        account.reserved.insert(nonce, max_block);
+
+        NonceReservation {
+            address,
+            nonce,
+            max_block,
+        }
+    }
+
+    /// Releases a reservation so the nonce can be handed out again, e.g. after a bundle was cancelled.
+    ///
+    /// * `reservation` - The reservation to release
+    pub fn release(&self, reservation: &NonceReservation) {
+        if let Some(account) = self.accounts.lock().get_mut(&reservation.address) {
+            account.reserved.remove(&reservation.nonce);
+        }
+    }
+
+    /// Marks a reserved nonce as used on chain after its bundle was included.
+    ///
+    /// * `reservation` - The reservation of the included transaction
+    pub fn confirm(&self, reservation: &NonceReservation) {
+        let mut accounts = self.accounts.lock();
+        let account = accounts.entry(reservation.address).or_default();
+        if reservation.nonce >= account.next {
+            account.advance_to(reservation.nonce + U256::one());
+        }
+    }
+
+    /// Releases the reservations whose bundles can no longer be included at `block`.
+    ///
+    /// Nonces are released from the highest reserved one down. An expired nonce below a nonce
+    /// that is still reserved stays reserved, handing it out again could get it included before
+    /// the bundle holding the higher nonce and leave a gap below that one.
+    ///
+    /// * `block` - The current block number
+    pub fn on_block(&self, block: U64) {
+        for account in self.accounts.lock().values_mut() {
+            while account
+                .reserved
+                .last_key_value()
+                .is_some_and(|(_, max_block)| *max_block < block)
+            {
+                account.reserved.pop_last();
+            }
+        }
+    }
+
+    /// Returns the next nonce that would be reserved for `address`
+    pub fn peek(&self, address: Address) -> U256 {
+        self.accounts
+            .lock()
+            .get(&address)
+            .map(AccountNonces::lowest_free)
+            .unwrap_or_default()
+    }
+}
+
+impl Bundle {
+    /// Reserves a nonce for the signer, signs the transaction with it and appends it to the bundle body.
+    ///
+    /// The reservation is valid until the bundle's `max_block`, or its target `block` if unset.
+    /// If signing fails the nonce is released again.
+    ///
+    /// * `nonces` - Nonce manager shared between all bundles of the signer
+    /// * `signer` - Signer of the transaction, e.g. an ethers `LocalWallet`
+    /// * `network` - The network the bundle will be sent to
+    /// * `tx` - The transaction to sign, its nonce is overwritten
+    /// * `can_revert` - If true, the transaction can revert without the bundle being considered invalid
+    pub async fn push_signed_with_nonce<S: Signer>(
+        &mut self,
+        nonces: &NonceManager,
+        signer: &S,
+        network: &MatchMakerNetwork,
+        mut tx: TypedTransaction,
+        can_revert: bool,
+    ) -> Result<NonceReservation, S::Error> {
+        let max_block = self.inclusion.max_block.unwrap_or(self.inclusion.block);
+        let reservation = nonces.reserve(signer.address(), max_block);
+        tx.set_nonce(reservation.nonce);
+
+        match self.push_signed(signer, network, tx, can_revert).await {
+            Ok(_) => Ok(reservation),
+            Err(err) => {
+                nonces.release(&reservation);
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! A layer enforcing client-side rate limits on API requests, see [`RateLimitConfig`]
+//!
+//! Every method with its own [`RateBudget`] gets a token bucket, all other methods share the bucket
+//! of the default budget. Requests that exceed their budget are queued or dropped depending on the
+//! [`ThrottlePolicy`].
+use crate::{
+    config::{RateBudget, RateLimitConfig, ThrottlePolicy},
+    jsonrpc, metrics,
+};
+use futures_util::future::BoxFuture;
+use http::Request;
+use hyper::{Body, Response};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+use tracing::{debug, warn};
+
+/// JSON-RPC error code of requests dropped by the rate limiter
+///
+/// Distinct from the `-32005` servers use for rate limiting, which is retried by default, so
+/// client-side throttling can be told apart from the matchmaker's.
+pub const THROTTLED_ERROR_CODE: i64 = -32099;
+
+#[derive(Debug)]
+struct TokenBucket {
+    budget: RateBudget,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(budget: RateBudget) -> Self {
+        Self {
+            budget,
+            tokens: budget.burst as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.budget.per_second).min(self.budget.burst as f64);
+        self.refilled_at = now;
+    }
+
+    /// Takes a token if one is available
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes a token, going into debt if none is available, and returns how long to wait until the
+    /// debt is paid off. Later requests queue behind the earlier ones.
+    ///
+    /// Budgets are checked with [`RateBudget::validate`] when the client is built, so the refill
+    /// rate is positive and finite.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.budget.per_second)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+/// Bucket key shared by all methods without their own budget
+const DEFAULT_BUCKET: &str = "*";
+
+impl RateLimiter {
+    /// Returns how long the request has to wait for its budget, or an error if it exceeded the
+    /// budget and has to be dropped
+    fn acquire(&self, method: &str) -> Result<Duration, ()> {
+        let (key, budget) = match self.config.methods.get(method) {
+            Some(budget) => (method, *budget),
+            None => match self.config.default {
+                Some(budget) => (DEFAULT_BUCKET, budget),
+                None => return Ok(Duration::ZERO),
+            },
+        };
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(budget));
+
+        match self.config.policy {
+            ThrottlePolicy::Queue => Ok(bucket.reserve(now)),
+            ThrottlePolicy::Drop if bucket.try_acquire(now) => Ok(Duration::ZERO),
+            ThrottlePolicy::Drop => Err(()),
+        }
+    }
+}
+
+/// Layer that applies [`RateLimit`], which throttles requests exceeding their rate budget
+#[derive(Clone)]
+pub(crate) struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        RateLimitLayer {
+            limiter: Arc::new(RateLimiter {
+                config,
+                buckets: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+impl<I> Layer<I> for RateLimitLayer {
+    type Service = RateLimit<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        RateLimit {
+            limiter: self.limiter.clone(),
+            inner,
+        }
+    }
+}
+
+/// Middleware that queues or drops requests exceeding their rate budget.
+///
+/// Dropped requests are answered with a JSON-RPC error with code [`THROTTLED_ERROR_CODE`] without
+/// being sent. The response has status `200`, like errors of the matchmaker, so the JSON-RPC client
+/// parses the error instead of failing with the HTTP status. Clones of the client share the same
+/// budgets.
+#[derive(Clone)]
+pub struct RateLimit<I> {
+    limiter: Arc<RateLimiter>,
+    inner: I,
+}
+
+impl<I> Service<Request<Body>> for RateLimit<I>
+where
+    I: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    I::Future: Send,
+    I::Error: From<hyper::Error>,
+{
+    type Response = I::Response;
+    type Error = I::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        // wait for service to be ready
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+
+        if limiter.config.is_unlimited() {
+            return Box::pin(inner.call(request));
+        }
+
+        let (parts, body) = request.into_parts();
+
+        Box::pin(async move {
+            let body_bytes = hyper::body::to_bytes(body).await?;
+            let method =
+                jsonrpc::request_method(&body_bytes).unwrap_or_else(|| "batch".to_string());
+
+            match limiter.acquire(&method) {
+                Ok(wait) if wait.is_zero() => {}
+                Ok(wait) => {
+                    debug!(method, ?wait, "Request exceeded rate budget, queueing");
+                    metrics::record_throttled(&method, "queue", Some(wait));
+                    tokio::time::sleep(wait).await;
+                }
+                Err(()) => {
+                    warn!(method, "Request exceeded rate budget, dropping");
+                    metrics::record_throttled(&method, "drop", None);
+                    let id = jsonrpc::request_id(&body_bytes).unwrap_or_default();
+                    let body = jsonrpc::error_response(
+                        id,
+                        THROTTLED_ERROR_CODE,
+                        "request dropped by client-side rate limit",
+                    );
+                    return Ok(Response::new(Body::from(body)));
+                }
+            }
+
+            inner
+                .call(Request::from_parts(parts, Body::from(body_bytes)))
+                .await
+        })
+    }
+}
@@ -0,0 +1,186 @@
+//! A layer retrying API requests that failed with transient errors, see [`RetryConfig`]
+//!
+//! The request body is buffered once and every attempt sends the same bytes, so a retried
+//! `mev_sendBundle` carries the identical bundle and is deduped by the matchmaker.
+//!
+//! Retries stop at the deadline of the request. Bundles sent with
+//! [`MatchmakerClient::send_bundle`](crate::client::MatchmakerClient::send_bundle) are retried until
+//! the last block they target is due, see [`RetryConfig::block_time_ms`]. Callers that know better
+//! when a request becomes useless pass that time with [`with_deadline`]; other requests are retried
+//! until [`RetryConfig::deadline`] after the first attempt.
+use crate::{config::RetryConfig, jsonrpc, types::InclusionParams};
+use futures_util::future::BoxFuture;
+use http::{Request, StatusCode};
+use hyper::{body::Bytes, Body, Response};
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+use tracing::warn;
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Runs `requests`, retrying the requests it sends no later than `deadline`
+///
+/// The first attempt of a request is always sent, even after the deadline. Nested calls keep the
+/// earlier deadline.
+///
+/// * `deadline` - Time after which no more retries are started
+/// * `requests` - Future sending the requests, e.g. [`MatchmakerClient::send_bundle`](crate::client::MatchmakerClient::send_bundle)
+pub async fn with_deadline<F: Future>(deadline: Instant, requests: F) -> F::Output {
+    let deadline = current_deadline().map_or(deadline, |outer| outer.min(deadline));
+    DEADLINE.scope(deadline, requests).await
+}
+
+/// Time at which the last block a bundle targets is due, assuming its target block is the next one
+///
+/// * `inclusion` - Target and maximum block of the bundle
+/// * `block_time` - Time between two blocks
+pub(crate) fn bundle_deadline(
+    inclusion: &InclusionParams,
+    block_time: Duration,
+) -> Option<Instant> {
+    let max_block = inclusion.max_block.unwrap_or(inclusion.block);
+    let blocks = max_block
+        .saturating_sub(inclusion.block)
+        .as_u64()
+        .saturating_add(1);
+    u32::try_from(blocks)
+        .ok()
+        .and_then(|blocks| block_time.checked_mul(blocks))
+        .and_then(|window| Instant::now().checked_add(window))
+}
+
+/// Deadline set by the enclosing [`with_deadline`], if any
+fn current_deadline() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Layer that applies [`Retry`], which retries requests that failed with transient errors
+#[derive(Clone)]
+pub(crate) struct RetryLayer {
+    config: Arc<RetryConfig>,
+}
+
+impl RetryLayer {
+    pub(crate) fn new(config: RetryConfig) -> Self {
+        RetryLayer {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<I> Layer<I> for RetryLayer {
+    type Service = Retry<I>;
+
+    fn layer(&self, inner: I) -> Self::Service {
+        Retry {
+            config: self.config.clone(),
+            inner,
+        }
+    }
+}
+
+/// Middleware that resends requests with the same body until they succeed, fail with an error that
+/// is not retryable, the attempts of the [`RetryConfig`] are exhausted or the deadline has passed.
+#[derive(Clone)]
+pub struct Retry<I> {
+    config: Arc<RetryConfig>,
+    inner: I,
+}
+
+impl<I> Service<Request<Body>> for Retry<I>
+where
+    I: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    I::Future: Send,
+    I::Error: Send + From<hyper::Error>,
+{
+    type Response = I::Response;
+    type Error = I::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        // wait for service to be ready
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let config = self.config.clone();
+
+        if config.max_attempts <= 1 {
+            return Box::pin(inner.call(request));
+        }
+
+        let (parts, body) = request.into_parts();
+        // read here, the returned future may be polled outside of the caller's scope
+        let deadline = current_deadline().unwrap_or_else(|| Instant::now() + config.deadline());
+
+        Box::pin(async move {
+            let body_bytes = hyper::body::to_bytes(body).await?;
+            let mut attempt = 1;
+
+            loop {
+                let give_up =
+                    attempt >= config.max_attempts || Instant::now() + config.backoff() > deadline;
+
+                let request = copy_request(&parts, body_bytes.clone());
+                let response = match inner.clone().call(request).await {
+                    Ok(response) => response,
+                    Err(err) if give_up => return Err(err),
+                    Err(_) => {
+                        warn!(attempt, "Request failed, retrying");
+                        tokio::time::sleep(config.backoff()).await;
+                        attempt += 1;
+                        continue;
+                    }
+                };
+
+                let (response_parts, body) = response.into_parts();
+                let response_bytes = match hyper::body::to_bytes(body).await {
+                    Ok(response_bytes) => response_bytes,
+                    Err(err) => {
+                        let body = Body::wrap_stream(futures_util::stream::once(async move {
+                            Err::<Bytes, _>(err)
+                        }));
+                        return Ok(Response::from_parts(response_parts, body));
+                    }
+                };
+
+                let retryable = is_retryable_status(response_parts.status)
+                    || jsonrpc::response_error_code(&response_bytes)
+                        .is_some_and(|code| config.retryable_codes.contains(&code));
+                if !retryable || give_up {
+                    return Ok(Response::from_parts(
+                        response_parts,
+                        Body::from(response_bytes),
+                    ));
+                }
+
+                warn!(attempt, status = %response_parts.status, "Request failed, retrying");
+                tokio::time::sleep(config.backoff()).await;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Rebuilds a request from the parts of the original one, extensions are not copied
+fn copy_request(parts: &http::request::Parts, body: Bytes) -> Request<Body> {
+    let mut request = Request::new(Body::from(body));
+    *request.method_mut() = parts.method.clone();
+    *request.uri_mut() = parts.uri.clone();
+    *request.version_mut() = parts.version;
+    *request.headers_mut() = parts.headers.clone();
+    request
+}
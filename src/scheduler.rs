@@ -0,0 +1,240 @@
+//! Resubmission of bundles every block until they land or expire
+//!
+//! A bundle targets a single block, so a bundle that is not included in its target block has to
+//! be sent again for the next one. The [`BundleScheduler`] does this for every block until the
+//! bundle's `max_block`.
+//!
+//! Retries of a resubmission stop once its target block has been built, the time of which is
+//! estimated from the arrival of the previous block and the block time.
+use crate::{
+    blocks::BlockSource,
+    client::MatchmakerClient,
+    retry,
+    types::{Bundle, BundleTx},
+};
+use ethers::{
+    providers::Middleware,
+    signers::Signer,
+    types::{H256, U64},
+};
+use futures_util::{
+    future::{select, Either},
+    StreamExt,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+use tracing::{debug, info, instrument, warn};
+
+/// Final status of a scheduled bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleStatus {
+    /// The bundle's transactions were included in `block`
+    Landed {
+        /// Block the bundle was included in
+        block: U64,
+    },
+    /// The schedule was cancelled with its [`CancelHandle`]
+    Cancelled,
+    /// The bundle was not included up to its `max_block`
+    Expired,
+    /// The block source ended before the bundle landed or expired
+    BlocksEnded,
+}
+
+/// A submission of the bundle for a target block
+#[derive(Debug, Clone)]
+pub struct Submission {
+    /// The block the bundle was sent for
+    pub target_block: U64,
+    /// The bundle hash returned by the matchmaker, or the error the submission failed with
+    pub result: Result<H256, String>,
+}
+
+/// Outcome of [`BundleScheduler::run`]
+#[derive(Debug, Clone)]
+pub struct ScheduleReport {
+    /// Final status of the bundle
+    pub status: ScheduleStatus,
+    /// All submissions of the bundle, in the order they were made
+    pub submissions: Vec<Submission>,
+}
+
+/// Cancels a running schedule. Cancelling stops resubmission, it does not withdraw bundles that
+/// were already sent, see [`MatchmakerClient::cancel_bundle`] for that.
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+    cancelled: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancelHandle {
+    fn default() -> Self {
+        Self {
+            cancelled: Arc::new(watch::channel(false).0),
+        }
+    }
+}
+
+impl CancelHandle {
+    /// Creates a handle that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all schedules run with this handle or its clones
+    pub fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+
+    /// Returns true if the handle was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    async fn cancelled(&self) {
+        let mut receiver = self.cancelled.subscribe();
+        while !*receiver.borrow_and_update() {
+            // the sender lives as long as `self`, so this never fails
+            let _ = receiver.changed().await;
+        }
+    }
+}
+
+/// Resubmits a bundle every block until it lands, is cancelled or passes its `max_block`
+///
+/// The bundle has landed once all of its own signed transactions have a receipt. Bundles without
+/// signed transactions, e.g. a bundle of tx hashes only, can not be detected as landed and are
+/// resubmitted until they expire.
+pub struct BundleScheduler<S, M> {
+    client: MatchmakerClient<S>,
+    provider: M,
+    block_time: Duration,
+}
+
+impl<S, M> BundleScheduler<S, M>
+where
+    S: Signer + Clone + 'static,
+    M: Middleware,
+{
+    /// Creates a scheduler
+    ///
+    /// * `client` - Client the bundle is sent with
+    /// * `provider` - Provider used to look up receipts of the bundle's transactions
+    pub fn new(client: MatchmakerClient<S>, provider: M) -> Self {
+        Self {
+            client,
+            provider,
+            block_time: Duration::from_secs(12),
+        }
+    }
+
+    /// Sets the time between two blocks, 12 seconds by default. Retries of a resubmission stop
+    /// once its target block is due.
+    pub fn with_block_time(mut self, block_time: Duration) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// Sends `bundle` for its target block and then again for every new block of `blocks` until
+    /// it lands, `cancel` is cancelled or the bundle's `max_block` has passed.
+    ///
+    /// The bundle's `inclusion.block` is updated to the next block on every resubmission,
+    /// `inclusion.max_block` defaults to the target block if unset.
+    ///
+    /// * `bundle` - The bundle to schedule
+    /// * `blocks` - Source of new block numbers
+    /// * `cancel` - Handle to stop the schedule early
+    #[instrument(skip_all, fields(bundle_hash = ?bundle.hash(), block = %bundle.inclusion.block))]
+    pub async fn run<B: BlockSource>(
+        &self,
+        mut bundle: Bundle,
+        blocks: &B,
+        cancel: &CancelHandle,
+    ) -> ScheduleReport {
+        let max_block = bundle.inclusion.max_block.unwrap_or(bundle.inclusion.block);
+        bundle.inclusion.max_block = Some(max_block);
+        let tx_hashes: Vec<H256> = bundle
+            .body
+            .iter()
+            .filter(|tx| matches!(tx, BundleTx::Tx { .. }))
+            .map(BundleTx::hash)
+            .collect();
+
+        let mut submissions = Vec::new();
+        if cancel.is_cancelled() {
+            return ScheduleReport {
+                status: ScheduleStatus::Cancelled,
+                submissions,
+            };
+        }
+        submissions.push(self.submit(&bundle, None).await);
+
+        let mut block_numbers = blocks.block_numbers();
+        let status = loop {
+            let block = match select(Box::pin(cancel.cancelled()), block_numbers.next()).await {
+                Either::Left(_) => break ScheduleStatus::Cancelled,
+                Either::Right((None, _)) => break ScheduleStatus::BlocksEnded,
+                Either::Right((Some(block), _)) => block,
+            };
+            let seen_at = Instant::now();
+            debug!(%block, "New block");
+
+            if let Some(landed) = self.landed_in(&tx_hashes).await {
+                break ScheduleStatus::Landed { block: landed };
+            }
+            if block >= max_block {
+                break ScheduleStatus::Expired;
+            }
+
+            bundle.inclusion.block = bundle.inclusion.block.max(block + 1);
+            let deadline = u32::try_from((bundle.inclusion.block - block).as_u64())
+                .ok()
+                .and_then(|blocks_ahead| self.block_time.checked_mul(blocks_ahead))
+                .and_then(|wait| seen_at.checked_add(wait));
+            submissions.push(self.submit(&bundle, deadline).await);
+        };
+
+        info!(
+            ?status,
+            submissions = submissions.len(),
+            "Schedule finished"
+        );
+        ScheduleReport {
+            status,
+            submissions,
+        }
+    }
+
+    /// Sends `bundle`, retrying failed attempts until `deadline` if set
+    async fn submit(&self, bundle: &Bundle, deadline: Option<Instant>) -> Submission {
+        let result = match deadline {
+            Some(deadline) => retry::with_deadline(deadline, self.client.send_bundle(bundle)).await,
+            None => self.client.send_bundle(bundle).await,
+        }
+        .map(|response| response.bundle_hash)
+        .map_err(|err| err.to_string());
+
+        Submission {
+            target_block: bundle.inclusion.block,
+            result,
+        }
+    }
+
+    /// Returns the block the transactions were included in, if all of them have a receipt
+    async fn landed_in(&self, tx_hashes: &[H256]) -> Option<U64> {
+        let mut block = None;
+        for hash in tx_hashes {
+            match self.provider.get_transaction_receipt(*hash).await {
+                Ok(Some(receipt)) => block = block.max(receipt.block_number),
+                Ok(None) => return None,
+                Err(err) => {
+                    warn!(error = %err, tx_hash = ?hash, "Failed to get receipt");
+                    return None;
+                }
+            }
+        }
+        block
+    }
+}
@@ -0,0 +1,221 @@
+//! Function selector registry decoding the calldata of hinted transactions
+//!
+//! The [`SelectorRegistry`] maps 4-byte function selectors to ABI functions. It comes seeded with
+//! the swap methods of the Uniswap routers, WETH and ERC-20 methods and can be extended with
+//! human-readable signatures or JSON ABI files. Hints that only share the selector can be
+//! identified with [`SelectorRegistry::function`], hints that share the calldata are decoded into
+//! named parameters with [`SelectorRegistry::decode`].
+use crate::types::{PendingBundle, PendingTransaction};
+use anyhow::{Context, Result};
+use ethers::abi::{Abi, Function, HumanReadableParser, Token};
+use ethers::types::Address;
+use std::{collections::HashMap, path::Path};
+
+/// Functions the default registry is seeded with
+const DEFAULT_SIGNATURES: &[&str] = &[
+    // Uniswap V2 router
+    "function swapExactTokensForTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "function swapTokensForExactTokens(uint256 amountOut, uint256 amountInMax, address[] path, address to, uint256 deadline)",
+    "function swapExactETHForTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "function swapTokensForExactETH(uint256 amountOut, uint256 amountInMax, address[] path, address to, uint256 deadline)",
+    "function swapExactTokensForETH(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "function swapETHForExactTokens(uint256 amountOut, address[] path, address to, uint256 deadline)",
+    "function swapExactTokensForTokensSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "function swapExactETHForTokensSupportingFeeOnTransferTokens(uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    "function swapExactTokensForETHSupportingFeeOnTransferTokens(uint256 amountIn, uint256 amountOutMin, address[] path, address to, uint256 deadline)",
+    // Uniswap V3 router
+    "function exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160) params)",
+    "function exactInput((bytes,address,uint256,uint256,uint256) params)",
+    "function exactOutputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160) params)",
+    "function exactOutput((bytes,address,uint256,uint256,uint256) params)",
+    // Uniswap V3 router 02, without deadlines
+    "function exactInputSingle((address,address,uint24,address,uint256,uint256,uint160) params)",
+    "function exactInput((bytes,address,uint256,uint256) params)",
+    "function exactOutputSingle((address,address,uint24,address,uint256,uint256,uint160) params)",
+    "function exactOutput((bytes,address,uint256,uint256) params)",
+    "function multicall(bytes[] data)",
+    "function multicall(uint256 deadline, bytes[] data)",
+    // Uniswap universal router
+    "function execute(bytes commands, bytes[] inputs)",
+    "function execute(bytes commands, bytes[] inputs, uint256 deadline)",
+    // ERC-20 & WETH
+    "function transfer(address to, uint256 amount)",
+    "function transferFrom(address from, address to, uint256 amount)",
+    "function approve(address spender, uint256 amount)",
+    "function deposit()",
+    "function withdraw(uint256 wad)",
+];
+
+/// Maps function selectors to ABI functions, see the [module docs](self)
+#[derive(Debug, Clone, Default)]
+pub struct SelectorRegistry {
+    functions: HashMap<[u8; 4], Function>,
+}
+
+/// Calldata decoded with a registered function
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    /// Name of the function, e.g. `swapExactTokensForTokens`
+    pub name: String,
+    /// Signature of the function, e.g. `approve(address,uint256)`
+    pub signature: String,
+    /// Parameter names and values, in ABI order. Unnamed parameters have an empty name.
+    pub params: Vec<(String, Token)>,
+}
+
+impl SelectorRegistry {
+    /// Creates a registry seeded with the swap methods of the Uniswap routers, WETH and ERC-20
+    /// methods
+    pub fn new() -> Self {
+        let mut registry = Self::default();
+        registry
+            .register_signatures(DEFAULT_SIGNATURES)
+            .expect("default signatures are valid");
+        registry
+    }
+
+    /// Registers a function, replacing and returning a function with the same selector
+    pub fn register(&mut self, function: Function) -> Option<Function> {
+        self.functions.insert(function.short_signature(), function)
+    }
+
+    /// Registers functions given as human-readable signatures and returns their number
+    ///
+    /// Fails on the first invalid signature, keeping the functions registered before it.
+    ///
+    /// * `signatures` - Signatures such as `function approve(address spender, uint256 amount)`
+    pub fn register_signatures(&mut self, signatures: &[&str]) -> Result<usize> {
+        for signature in signatures {
+            let function = HumanReadableParser::parse_function(signature)
+                .with_context(|| format!("invalid function signature `{signature}`"))?;
+            self.register(function);
+        }
+        Ok(signatures.len())
+    }
+
+    /// Registers all functions of a contract ABI and returns their number
+    pub fn register_abi(&mut self, abi: &Abi) -> usize {
+        abi.functions()
+            .map(|function| self.register(function.clone()))
+            .count()
+    }
+
+    /// Registers all functions of a JSON ABI file, e.g. one produced by `solc` or a block explorer
+    ///
+    /// * `path` - Path of the JSON ABI
+    pub fn register_abi_file(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read ABI from {}", path.display()))?;
+        let abi: Abi = serde_json::from_str(&contents)
+            .with_context(|| format!("invalid JSON ABI in {}", path.display()))?;
+        Ok(self.register_abi(&abi))
+    }
+
+    /// Returns the function registered for `selector`
+    pub fn function(&self, selector: [u8; 4]) -> Option<&Function> {
+        self.functions.get(&selector)
+    }
+
+    /// Returns the number of registered functions
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Returns true if no functions are registered
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Decodes calldata with the function registered for its selector
+    ///
+    /// Returns `None` if the selector is not registered or the calldata does not match the
+    /// function's parameters.
+    ///
+    /// * `calldata` - Selector followed by the ABI encoded parameters
+    pub fn decode(&self, calldata: &[u8]) -> Option<DecodedCall> {
+        let selector: [u8; 4] = calldata.get(..4)?.try_into().ok()?;
+        let function = self.function(selector)?;
+        let tokens = function.decode_input(&calldata[4..]).ok()?;
+
+        Some(DecodedCall {
+            name: function.name.clone(),
+            signature: function.signature(),
+            params: function
+                .inputs
+                .iter()
+                .map(|input| input.name.clone())
+                .zip(tokens)
+                .collect(),
+        })
+    }
+
+    /// Returns the function a hinted transaction calls, `None` if the hint does not share the
+    /// selector or it is not registered
+    pub fn transaction_function(&self, tx: &PendingTransaction) -> Option<&Function> {
+        self.function(tx.function_selector.as_ref()?.0)
+    }
+
+    /// Decodes the calldata of a hinted transaction, `None` if the hint does not share the calldata
+    pub fn decode_transaction(&self, tx: &PendingTransaction) -> Option<DecodedCall> {
+        self.decode(tx.calldata.as_ref()?)
+    }
+
+    /// Decodes the calldata of all transactions of a hinted bundle that share it, in bundle order
+    pub fn decode_bundle(&self, bundle: &PendingBundle) -> Vec<DecodedCall> {
+        bundle
+            .transactions
+            .iter()
+            .flatten()
+            .filter_map(|tx| self.decode(&tx.calldata))
+            .collect()
+    }
+}
+
+impl DecodedCall {
+    /// Returns the value of the named parameter
+    pub fn param(&self, name: &str) -> Option<&Token> {
+        self.params
+            .iter()
+            .find_map(|(param, token)| (param == name).then_some(token))
+    }
+
+    /// Returns the token path of a swap, in the order it is encoded
+    ///
+    /// Supports `address[] path` parameters of the Uniswap V2 router, the packed `bytes` paths of
+    /// Uniswap V3 `exactInput`/`exactOutput`, which are reversed for exact output swaps, and the
+    /// token in and out of `exactInputSingle`/`exactOutputSingle`.
+    pub fn path(&self) -> Option<Vec<Address>> {
+        match self.param("path") {
+            Some(Token::Array(path)) => {
+                return path.iter().cloned().map(Token::into_address).collect()
+            }
+            Some(Token::Bytes(path)) => return packed_path(path),
+            _ => {}
+        }
+
+        let Some(Token::Tuple(params)) = self.param("params") else {
+            return None;
+        };
+        match (self.name.as_str(), params.as_slice()) {
+            ("exactInput" | "exactOutput", [Token::Bytes(path), ..]) => packed_path(path),
+            (
+                "exactInputSingle" | "exactOutputSingle",
+                [Token::Address(token_in), Token::Address(token_out), ..],
+            ) => Some(vec![*token_in, *token_out]),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes a Uniswap V3 path of 20 byte tokens separated by 3 byte fees
+fn packed_path(path: &[u8]) -> Option<Vec<Address>> {
+    if path.len() < 20 || !(path.len() - 20).is_multiple_of(23) {
+        return None;
+    }
+    Some(
+        path.chunks(23)
+            .map(|hop| Address::from_slice(&hop[..20]))
+            .collect(),
+    )
+}
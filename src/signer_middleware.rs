@@ -8,6 +8,7 @@
 use std::{
     sync::Arc,
     task::{Context, Poll},
+    time::Instant,
 };
 
 use ethers::{signers::Signer, types::H256, utils::keccak256};
@@ -17,6 +18,7 @@ use http::{header::HeaderValue, HeaderName, Request};
 use hyper::Body;
 
 use tower::{Layer, Service};
+use tracing::trace;
 
 /// Layer that applies [`FlashbotsSigner`] which adds a request header with a signed payload.
 #[derive(Clone)]
@@ -75,15 +77,8 @@ where
             let body_bytes = hyper::body::to_bytes(body).await.unwrap();
 
             // sign request body and insert header
-            let signature = signer
-                .sign_message(format!("0x{:x}", H256::from(keccak256(body_bytes.clone()))))
-                .await
-                .unwrap();
-
-            let header_name = HeaderName::from_static("x-flashbots-signature");
-            let header_val =
-                HeaderValue::from_str(&format!("{:?}:0x{}", signer.address(), signature)).unwrap();
-            parts.headers.insert(header_name, header_val);
+            let header_val = sign_body(signer.as_ref(), &body_bytes).await.unwrap();
+            parts.headers.insert(SIGNATURE_HEADER, header_val);
 
             let request = Request::from_parts(parts, Body::from(body_bytes.clone()));
 
@@ -91,3 +86,18 @@ where
         })
     }
 }
+
+/// Header carrying the Flashbots authentication signature
+pub(crate) const SIGNATURE_HEADER: HeaderName = HeaderName::from_static("x-flashbots-signature");
+
+/// Signs a request body and returns the value of the `X-Flashbots-Signature` header
+pub(crate) async fn sign_body<S: Signer>(signer: &S, body: &[u8]) -> Result<HeaderValue, S::Error> {
+    let start = Instant::now();
+    let signature = signer
+        .sign_message(format!("0x{:x}", H256::from(keccak256(body))))
+        .await?;
+    crate::metrics::observe_signing(start.elapsed());
+    trace!(signer = ?signer.address(), "Signed request body");
+
+    Ok(HeaderValue::from_str(&format!("{:?}:0x{}", signer.address(), signature)).unwrap())
+}
@@ -0,0 +1,313 @@
+//! Pipeline from hints of the event stream to bundles sent to the matchmaker
+//!
+//! [`Classifier`]s inspect every hint and report [`Opportunity`]s, [`Strategy`]s turn the
+//! opportunities they accept into bundles which the [`StrategyPipeline`] sends with
+//! [`MatchmakerClient::send_bundle`].
+//!
+//! Every strategy has a limit of concurrently built and sent bundles. Hints are only useful for a
+//! few blocks, so opportunities arriving while a strategy is at its limit are skipped instead of
+//! queued.
+//!
+//! The `uniswap_v2` module, enabled with the `decoders` feature, holds a reference strategy
+//! backrunning swaps on Uniswap V2 style pairs.
+#[cfg(feature = "decoders")]
+pub mod uniswap_v2;
+
+use crate::{
+    client::MatchmakerClient,
+    types::{Bundle, PendingTxOrBundle, StreamingEventTypes},
+};
+use anyhow::Result;
+use ethers::{signers::Signer, types::TxHash};
+use futures_util::{
+    future::{join, select, BoxFuture, Either},
+    stream::{self, FuturesUnordered, Stream, StreamExt},
+};
+use std::{any::Any, fmt, sync::Arc};
+use tokio::sync::{mpsc, Semaphore};
+use tracing::{debug, info, warn};
+
+/// An opportunity found in a hint by a [`Classifier`]
+#[derive(Clone)]
+pub struct Opportunity {
+    /// Kind of the opportunity, used by strategies to pick the opportunities they handle
+    pub kind: String,
+    /// The hint the opportunity was found in
+    pub hint: PendingTxOrBundle,
+    data: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl Opportunity {
+    /// Creates an opportunity
+    ///
+    /// * `kind` - Kind of the opportunity, e.g. "uniswap-v2-swap"
+    /// * `hint` - The hint the opportunity was found in
+    pub fn new(kind: impl Into<String>, hint: PendingTxOrBundle) -> Self {
+        Self {
+            kind: kind.into(),
+            hint,
+            data: None,
+        }
+    }
+
+    /// Attaches details found by the classifier, e.g. the decoded swap, for strategies to read
+    /// with [`Self::data`]
+    pub fn with_data<T: Any + Send + Sync>(mut self, data: T) -> Self {
+        self.data = Some(Arc::new(data));
+        self
+    }
+
+    /// Returns the details attached by the classifier, `None` if there are none or they are not a
+    /// `T`
+    pub fn data<T: Any>(&self) -> Option<&T> {
+        self.data.as_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Debug for Opportunity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Opportunity")
+            .field("kind", &self.kind)
+            .field("hint", &self.hint)
+            .field("has_data", &self.data.is_some())
+            .finish()
+    }
+}
+
+/// Finds opportunities in hints
+pub trait Classifier: Send + Sync {
+    /// Returns the opportunity found in `hint`, if any
+    fn classify(&self, hint: &PendingTxOrBundle) -> Option<Opportunity>;
+}
+
+/// Turns opportunities into bundles
+pub trait Strategy: Send + Sync {
+    /// Name of the strategy, used in logs
+    fn name(&self) -> &str;
+
+    /// Returns true if the strategy handles `opportunity`. Only accepted opportunities count
+    /// towards the strategy's concurrency limit.
+    fn accepts(&self, _opportunity: &Opportunity) -> bool {
+        true
+    }
+
+    /// Builds the bundle for `opportunity`, `None` if it is not worth a bundle
+    fn build<'a>(&'a self, opportunity: &'a Opportunity) -> BoxFuture<'a, Result<Option<Bundle>>>;
+}
+
+/// Result of a strategy handling an opportunity
+struct StrategyOutcome {
+    strategy: String,
+    hint_hash: TxHash,
+    /// Hash of the sent bundle, `None` if the strategy built no bundle
+    result: Result<Option<TxHash>, String>,
+}
+
+/// Counters of a pipeline run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Hints received
+    pub hints: usize,
+    /// Opportunities reported by classifiers
+    pub opportunities: usize,
+    /// Accepted opportunities skipped because their strategy was at its concurrency limit
+    pub throttled: usize,
+    /// Bundles sent successfully
+    pub sent: usize,
+    /// Opportunities the strategy built no bundle for
+    pub passed: usize,
+    /// Opportunities whose bundle failed to build or send
+    pub failed: usize,
+}
+
+impl PipelineReport {
+    fn record(&mut self, outcome: &StrategyOutcome) {
+        match outcome.result {
+            Ok(Some(_)) => self.sent += 1,
+            Ok(None) => self.passed += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
+}
+
+struct RegisteredStrategy {
+    strategy: Box<dyn Strategy>,
+    limit: Semaphore,
+}
+
+/// Runs classifiers and strategies on hints and sends the resulting bundles, see the
+/// [module docs](self)
+pub struct StrategyPipeline<S> {
+    client: MatchmakerClient<S>,
+    classifiers: Vec<Box<dyn Classifier>>,
+    strategies: Vec<RegisteredStrategy>,
+}
+
+impl<S> StrategyPipeline<S>
+where
+    S: Signer + Clone + 'static,
+{
+    /// Creates a pipeline without classifiers and strategies
+    ///
+    /// * `client` - Client the hints are received and the bundles are sent with
+    pub fn new(client: MatchmakerClient<S>) -> Self {
+        Self {
+            client,
+            classifiers: Vec::new(),
+            strategies: Vec::new(),
+        }
+    }
+
+    /// Adds a classifier. Every hint is passed to all classifiers.
+    pub fn with_classifier(mut self, classifier: impl Classifier + 'static) -> Self {
+        self.classifiers.push(Box::new(classifier));
+        self
+    }
+
+    /// Adds a strategy. Every opportunity is passed to all strategies accepting it.
+    ///
+    /// * `strategy` - The strategy
+    /// * `max_concurrency` - Maximum number of opportunities the strategy handles at once, at
+    ///   least 1
+    pub fn with_strategy(
+        mut self,
+        strategy: impl Strategy + 'static,
+        max_concurrency: usize,
+    ) -> Self {
+        self.strategies.push(RegisteredStrategy {
+            strategy: Box::new(strategy),
+            limit: Semaphore::new(max_concurrency.max(1)),
+        });
+        self
+    }
+
+    /// Subscribes to the event stream and handles its hints until the stream ends
+    ///
+    /// Fails if connecting to the event stream failed, see [`MatchmakerClient::on`].
+    ///
+    /// * `event_type` - Type of events to subscribe to
+    pub async fn run(&self, event_type: StreamingEventTypes) -> Result<PipelineReport> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let hints = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|hint| (hint, receiver))
+        });
+        let subscription = self.client.on(event_type, move |hint| {
+            // the receiver lives until the stream ends
+            let _ = sender.send(hint);
+        });
+
+        let (subscribed, report) = join(subscription, self.run_hints(hints)).await;
+        subscribed?;
+        Ok(report)
+    }
+
+    /// Handles `hints` until the stream ends and all started strategies have finished
+    pub async fn run_hints(&self, hints: impl Stream<Item = PendingTxOrBundle>) -> PipelineReport {
+        let mut hints = Box::pin(hints);
+        let mut hints_ended = false;
+        let mut in_flight = FuturesUnordered::new();
+        let mut report = PipelineReport::default();
+
+        loop {
+            let next = match (hints_ended, in_flight.is_empty()) {
+                (true, true) => break,
+                (true, false) => Either::Right(in_flight.next().await),
+                (false, true) => Either::Left(hints.next().await),
+                (false, false) => match select(hints.next(), in_flight.next()).await {
+                    Either::Left((hint, _)) => Either::Left(hint),
+                    Either::Right((outcome, _)) => Either::Right(outcome),
+                },
+            };
+
+            match next {
+                Either::Left(Some(hint)) => self.dispatch(hint, &mut in_flight, &mut report),
+                Either::Left(None) => hints_ended = true,
+                Either::Right(Some(outcome)) => report.record(&outcome),
+                Either::Right(None) => {}
+            }
+        }
+
+        info!(?report, "Strategy pipeline finished");
+        report
+    }
+
+    /// Classifies `hint` and starts the strategies accepting its opportunities
+    fn dispatch<'a>(
+        &'a self,
+        hint: PendingTxOrBundle,
+        in_flight: &mut FuturesUnordered<BoxFuture<'a, StrategyOutcome>>,
+        report: &mut PipelineReport,
+    ) {
+        report.hints += 1;
+
+        for classifier in &self.classifiers {
+            let Some(opportunity) = classifier.classify(&hint) else {
+                continue;
+            };
+            report.opportunities += 1;
+            debug!(kind = %opportunity.kind, hint_hash = ?hint.hash(), "Found opportunity");
+
+            for registered in &self.strategies {
+                let strategy = &*registered.strategy;
+                if !strategy.accepts(&opportunity) {
+                    continue;
+                }
+                let Ok(permit) = registered.limit.try_acquire() else {
+                    debug!(
+                        strategy = strategy.name(),
+                        kind = %opportunity.kind,
+                        "Strategy at concurrency limit, skipping opportunity"
+                    );
+                    report.throttled += 1;
+                    continue;
+                };
+
+                let opportunity = opportunity.clone();
+                in_flight.push(Box::pin(async move {
+                    let outcome = self.execute(strategy, &opportunity).await;
+                    drop(permit);
+                    outcome
+                }));
+            }
+        }
+    }
+
+    async fn execute(&self, strategy: &dyn Strategy, opportunity: &Opportunity) -> StrategyOutcome {
+        let result = match strategy.build(opportunity).await {
+            Ok(Some(bundle)) => self
+                .client
+                .send_bundle(&bundle)
+                .await
+                .map(|response| Some(response.bundle_hash))
+                .map_err(|err| err.to_string()),
+            Ok(None) => Ok(None),
+            Err(err) => Err(err.to_string()),
+        };
+
+        let outcome = StrategyOutcome {
+            strategy: strategy.name().to_string(),
+            hint_hash: opportunity.hint.hash(),
+            result,
+        };
+        match &outcome.result {
+            Ok(Some(bundle_hash)) => info!(
+                strategy = %outcome.strategy,
+                hint_hash = ?outcome.hint_hash,
+                ?bundle_hash,
+                "Sent bundle"
+            ),
+            Ok(None) => debug!(
+                strategy = %outcome.strategy,
+                hint_hash = ?outcome.hint_hash,
+                "Strategy built no bundle"
+            ),
+            Err(err) => warn!(
+                strategy = %outcome.strategy,
+                hint_hash = ?outcome.hint_hash,
+                error = %err,
+                "Strategy failed"
+            ),
+        }
+        outcome
+    }
+}
@@ -0,0 +1,450 @@
+//! Reference backrun strategy for Uniswap V2 style pairs
+//!
+//! The [`UniswapV2SyncClassifier`] reports hints that share the `Sync` log of a configured pair,
+//! i.e. the pair's reserves after the hinted transaction. The [`UniswapV2Backrun`] strategy
+//! overlays these reserves on the last confirmed reserves of all configured pairs and looks for
+//! the most profitable cycle `base token -> token -> base token` through two pairs of the same
+//! tokens. The backrun calls an executor contract with the interface
+//!
+//! ```solidity
+//! function backrun(address firstPair, address secondPair, address tokenIn, uint256 amountIn, uint256 minAmountOut);
+//! ```
+//!
+//! which is expected to swap `amountIn` of `tokenIn` through both pairs and revert if less than
+//! `minAmountOut` comes back. Confirmed reserves are kept up to date with
+//! [`UniswapV2Backrun::sync`], e.g. from the `Sync` logs of new blocks, and the current block with
+//! [`UniswapV2Backrun::on_block`]. No chain access is needed otherwise.
+use super::{Classifier, Opportunity, Strategy};
+use crate::{
+    decoders::DecodedEvent,
+    nonce_manager::NonceManager,
+    types::{
+        Bundle, BundleTx, InclusionParams, MatchMakerNetwork, PendingTxOrBundle, Refund,
+        ValidityParams,
+    },
+};
+use anyhow::{anyhow, bail, Result};
+use ethers::{
+    abi::{encode, Token},
+    signers::Signer,
+    types::{Address, Bytes, Eip1559TransactionRequest, U256, U512, U64},
+    utils::id,
+};
+use futures_util::{future::BoxFuture, FutureExt};
+use parking_lot::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tracing::debug;
+
+/// Kind of the opportunities reported by [`UniswapV2SyncClassifier`]
+pub const UNISWAP_V2_SYNC: &str = "uniswap-v2-sync";
+
+/// Signature of the executor function called by the backrun
+const EXECUTOR_SIGNATURE: &str = "backrun(address,address,address,uint256,uint256)";
+
+/// Denominator of pair fees given in basis points
+const FEE_DENOMINATOR: u32 = 10_000;
+
+/// A Uniswap V2 style pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UniswapV2Pair {
+    /// Address of the pair
+    pub address: Address,
+    /// The pair's token0
+    pub token0: Address,
+    /// The pair's token1
+    pub token1: Address,
+    /// Swap fee in basis points, 30 for Uniswap V2
+    pub fee_bps: u32,
+}
+
+impl UniswapV2Pair {
+    /// Creates a pair with the Uniswap V2 fee of 0.3%
+    pub fn new(address: Address, token0: Address, token1: Address) -> Self {
+        Self {
+            address,
+            token0,
+            token1,
+            fee_bps: 30,
+        }
+    }
+
+    /// Sets the swap fee, in basis points
+    pub fn with_fee_bps(mut self, fee_bps: u32) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
+    /// Returns the reserves of `token_in` and the other token, `None` if the pair does not hold
+    /// `token_in`
+    fn oriented(&self, token_in: Address, reserves: Reserves) -> Option<(Address, Reserves)> {
+        if token_in == self.token0 {
+            Some((self.token1, reserves))
+        } else if token_in == self.token1 {
+            Some((self.token0, Reserves(reserves.1, reserves.0)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Reserves of a pair, in the order of its tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Reserves(U256, U256);
+
+/// Reserves of a pair after a hinted transaction, taken from its `Sync` log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HintedSync {
+    /// Address of the pair
+    pub pair: Address,
+    /// Reserve of token0
+    pub reserve0: U256,
+    /// Reserve of token1
+    pub reserve1: U256,
+}
+
+/// Reports hints sharing the `Sync` log of a configured pair as [`UNISWAP_V2_SYNC`] opportunities
+/// with the hinted syncs as a `Vec<HintedSync>` in [`Opportunity::data`]
+///
+/// Hints that share the topics of a `Sync` log but not its data are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct UniswapV2SyncClassifier {
+    pairs: HashSet<Address>,
+}
+
+impl UniswapV2SyncClassifier {
+    /// Creates a classifier for the given pairs
+    pub fn new(pairs: impl IntoIterator<Item = Address>) -> Self {
+        Self {
+            pairs: pairs.into_iter().collect(),
+        }
+    }
+}
+
+impl Classifier for UniswapV2SyncClassifier {
+    fn classify(&self, hint: &PendingTxOrBundle) -> Option<Opportunity> {
+        let logs = match hint {
+            PendingTxOrBundle::Tx(tx) => tx.decoded_logs(),
+            PendingTxOrBundle::Bundle(bundle) => bundle.decoded_logs(),
+        };
+        let syncs: Vec<HintedSync> = logs
+            .into_iter()
+            .filter(|log| self.pairs.contains(&log.address))
+            .filter_map(|log| match log.event {
+                DecodedEvent::UniswapV2Sync(sync) => Some(HintedSync {
+                    pair: log.address,
+                    reserve0: sync.reserve0.into(),
+                    reserve1: sync.reserve1.into(),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        (!syncs.is_empty())
+            .then(|| Opportunity::new(UNISWAP_V2_SYNC, hint.clone()).with_data(syncs))
+    }
+}
+
+/// Settings of the [`UniswapV2Backrun`] strategy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackrunConfig {
+    /// Token the arbitrage starts and ends with, e.g. WETH
+    pub base_token: Address,
+    /// Contract executing the arbitrage, see the [module docs](self)
+    pub executor: Address,
+    /// Percentage of the profit refunded to the hinted transaction's sender
+    pub refund_percent: u32,
+    /// Minimum profit in the base token to send a backrun
+    pub min_profit: U256,
+    /// Gas limit of the backrun transaction
+    pub gas_limit: U256,
+    /// Max fee per gas of the backrun transaction
+    pub max_fee_per_gas: U256,
+    /// Max priority fee per gas of the backrun transaction
+    pub max_priority_fee_per_gas: U256,
+    /// Number of blocks the bundle is valid for, starting with the next block
+    pub blocks: u64,
+}
+
+/// The most profitable cycle found for an opportunity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arbitrage {
+    /// Pair the base token is sold to
+    pub first_pair: Address,
+    /// Pair the base token is bought back from
+    pub second_pair: Address,
+    /// Amount of the base token sold to the first pair
+    pub amount_in: U256,
+    /// Amount of the base token gained, after pair fees
+    pub profit: U256,
+}
+
+/// Backruns hinted swaps on Uniswap V2 style pairs, see the [module docs](self)
+pub struct UniswapV2Backrun<S> {
+    config: BackrunConfig,
+    pairs: HashMap<Address, UniswapV2Pair>,
+    reserves: RwLock<HashMap<Address, Reserves>>,
+    block: RwLock<Option<U64>>,
+    signer: S,
+    network: MatchMakerNetwork,
+    nonces: Arc<NonceManager>,
+}
+
+impl<S: Signer> UniswapV2Backrun<S> {
+    /// Creates the strategy without pairs
+    ///
+    /// * `config` - Settings of the strategy
+    /// * `signer` - Signer of the backrun transactions
+    /// * `network` - The network the bundles are sent to
+    /// * `nonces` - Nonce manager shared by all bundles of the signer
+    pub fn new(
+        config: BackrunConfig,
+        signer: S,
+        network: MatchMakerNetwork,
+        nonces: Arc<NonceManager>,
+    ) -> Self {
+        Self {
+            config,
+            pairs: HashMap::new(),
+            reserves: RwLock::new(HashMap::new()),
+            block: RwLock::new(None),
+            signer,
+            network,
+            nonces,
+        }
+    }
+
+    /// Adds a pair. Pairs are only used once their reserves are known, see [`Self::sync`].
+    pub fn with_pair(mut self, pair: UniswapV2Pair) -> Self {
+        self.pairs.insert(pair.address, pair);
+        self
+    }
+
+    /// Returns a classifier for the strategy's pairs
+    pub fn classifier(&self) -> UniswapV2SyncClassifier {
+        UniswapV2SyncClassifier::new(self.pairs.keys().copied())
+    }
+
+    /// Sets the confirmed reserves of a pair, ignored for pairs that are not configured
+    pub fn sync(&self, pair: Address, reserve0: U256, reserve1: U256) {
+        if self.pairs.contains_key(&pair) {
+            self.reserves
+                .write()
+                .insert(pair, Reserves(reserve0, reserve1));
+        }
+    }
+
+    /// Returns the confirmed reserves of a pair
+    pub fn reserves(&self, pair: Address) -> Option<(U256, U256)> {
+        self.reserves
+            .read()
+            .get(&pair)
+            .map(|reserves| (reserves.0, reserves.1))
+    }
+
+    /// Sets the latest block, backruns target the block after it. Also expires the nonce
+    /// reservations of bundles that can no longer be included.
+    pub fn on_block(&self, block: U64) {
+        *self.block.write() = Some(block);
+        self.nonces.on_block(block);
+    }
+
+    /// Returns the most profitable arbitrage after the hinted syncs, `None` if there is none
+    pub fn find_arbitrage(&self, syncs: &[HintedSync]) -> Option<Arbitrage> {
+        let mut reserves = self.reserves.read().clone();
+        for sync in syncs {
+            if self.pairs.contains_key(&sync.pair) {
+                reserves.insert(sync.pair, Reserves(sync.reserve0, sync.reserve1));
+            }
+        }
+
+        let base = self.config.base_token;
+        syncs
+            .iter()
+            .filter_map(|sync| self.pairs.get(&sync.pair))
+            .flat_map(|hinted| {
+                self.pairs
+                    .values()
+                    .filter(move |other| other.address != hinted.address)
+                    .flat_map(move |other| [(hinted, other), (other, hinted)])
+            })
+            .filter_map(|(first, second)| cycle(base, first, second, &reserves))
+            .max_by_key(|arbitrage| arbitrage.profit)
+    }
+
+    async fn backrun(&self, opportunity: &Opportunity) -> Result<Option<Bundle>> {
+        let Some(syncs) = opportunity.data::<Vec<HintedSync>>() else {
+            return Ok(None);
+        };
+        let Some(arbitrage) = self.find_arbitrage(syncs) else {
+            return Ok(None);
+        };
+        if arbitrage.profit <= self.config.min_profit {
+            debug!(profit = %arbitrage.profit, "Arbitrage below minimum profit");
+            return Ok(None);
+        }
+        let Some(block) = *self.block.read() else {
+            bail!("latest block unknown");
+        };
+
+        let mut bundle = Bundle {
+            inclusion: InclusionParams {
+                block: block + 1,
+                max_block: Some(block + self.config.blocks.max(1)),
+            },
+            body: vec![BundleTx::TxHash {
+                hash: opportunity.hint.hash(),
+            }],
+            validity: Some(ValidityParams {
+                refund: Some(vec![Refund {
+                    body_idx: 0,
+                    percent: self.config.refund_percent,
+                }]),
+                refund_config: None,
+            }),
+            ..Default::default()
+        };
+        let tx = Eip1559TransactionRequest::new()
+            .to(self.config.executor)
+            .data(self.calldata(&arbitrage))
+            .gas(self.config.gas_limit)
+            .max_fee_per_gas(self.config.max_fee_per_gas)
+            .max_priority_fee_per_gas(self.config.max_priority_fee_per_gas);
+        bundle
+            .push_signed_with_nonce(&self.nonces, &self.signer, &self.network, tx.into(), false)
+            .await
+            .map_err(|err| anyhow!("failed to sign backrun: {err}"))?;
+
+        debug!(
+            first_pair = ?arbitrage.first_pair,
+            second_pair = ?arbitrage.second_pair,
+            amount_in = %arbitrage.amount_in,
+            profit = %arbitrage.profit,
+            "Built backrun"
+        );
+        Ok(Some(bundle))
+    }
+
+    fn calldata(&self, arbitrage: &Arbitrage) -> Bytes {
+        let params = encode(&[
+            Token::Address(arbitrage.first_pair),
+            Token::Address(arbitrage.second_pair),
+            Token::Address(self.config.base_token),
+            Token::Uint(arbitrage.amount_in),
+            Token::Uint(arbitrage.amount_in + self.config.min_profit),
+        ]);
+        [id(EXECUTOR_SIGNATURE).as_slice(), &params].concat().into()
+    }
+}
+
+impl<S: Signer> Strategy for UniswapV2Backrun<S> {
+    fn name(&self) -> &str {
+        "uniswap-v2-backrun"
+    }
+
+    fn accepts(&self, opportunity: &Opportunity) -> bool {
+        opportunity.kind == UNISWAP_V2_SYNC
+    }
+
+    fn build<'a>(&'a self, opportunity: &'a Opportunity) -> BoxFuture<'a, Result<Option<Bundle>>> {
+        self.backrun(opportunity).boxed()
+    }
+}
+
+/// Returns the arbitrage selling `base` to `first` and buying it back from `second`, `None` if
+/// the pairs do not trade the same tokens, their reserves are unknown or there is no profit
+fn cycle(
+    base: Address,
+    first: &UniswapV2Pair,
+    second: &UniswapV2Pair,
+    reserves: &HashMap<Address, Reserves>,
+) -> Option<Arbitrage> {
+    let (token, first_reserves) = first.oriented(base, *reserves.get(&first.address)?)?;
+    let (base_out, second_reserves) = second.oriented(token, *reserves.get(&second.address)?)?;
+    if base_out != base {
+        return None;
+    }
+
+    let amount_in = optimal_amount_in(
+        (first_reserves.0, first_reserves.1),
+        first.fee_bps,
+        (second_reserves.0, second_reserves.1),
+        second.fee_bps,
+    )?;
+    let bought = get_amount_out(amount_in, first_reserves.0, first_reserves.1, first.fee_bps);
+    let amount_out = get_amount_out(bought, second_reserves.0, second_reserves.1, second.fee_bps);
+    let profit = amount_out
+        .checked_sub(amount_in)
+        .filter(|profit| !profit.is_zero())?;
+
+    Some(Arbitrage {
+        first_pair: first.address,
+        second_pair: second.address,
+        amount_in,
+        profit,
+    })
+}
+
+/// Returns the output of a swap on a pair, rounded down like the Uniswap V2 router, zero if the
+/// pair has no reserves or the amounts are too large to compute
+///
+/// * `amount_in` - Amount sent to the pair
+/// * `reserve_in` - Reserve of the token sent to the pair
+/// * `reserve_out` - Reserve of the token sent by the pair
+/// * `fee_bps` - Swap fee of the pair, in basis points
+pub fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256, fee_bps: u32) -> U256 {
+    let amount_out = || {
+        let amount_in_with_fee = U512::from(amount_in)
+            .checked_mul(U512::from(FEE_DENOMINATOR.saturating_sub(fee_bps)))?;
+        let numerator = amount_in_with_fee.checked_mul(U512::from(reserve_out))?;
+        let denominator = U512::from(reserve_in)
+            .checked_mul(U512::from(FEE_DENOMINATOR))?
+            .checked_add(amount_in_with_fee)?;
+        // the output is below reserve_out
+        U256::try_from(numerator.checked_div(denominator)?).ok()
+    };
+    amount_out().unwrap_or_default()
+}
+
+/// Returns the input maximizing the profit of swapping through two pairs and back, `None` if no
+/// input is profitable
+///
+/// For a cycle through pairs with reserves `(a_in, a_out)` and `(b_in, b_out)` and fee factors
+/// `ga` and `gb`, the output is `x * ga * gb * a_out * b_out / (a_in * b_in + x * (ga * b_in + ga *
+/// gb * a_out))`, which minus `x` is maximal at `(sqrt(ga * gb * a_in * a_out * b_in * b_out) -
+/// a_in * b_in) / (ga * b_in + ga * gb * a_out)`.
+///
+/// * `first` - Reserves of the input token and the intermediate token in the first pair
+/// * `first_fee_bps` - Swap fee of the first pair, in basis points
+/// * `second` - Reserves of the intermediate token and the input token in the second pair
+/// * `second_fee_bps` - Swap fee of the second pair, in basis points
+pub fn optimal_amount_in(
+    first: (U256, U256),
+    first_fee_bps: u32,
+    second: (U256, U256),
+    second_fee_bps: u32,
+) -> Option<U256> {
+    let d = U512::from(FEE_DENOMINATOR);
+    let ga = U512::from(FEE_DENOMINATOR.saturating_sub(first_fee_bps));
+    let gb = U512::from(FEE_DENOMINATOR.saturating_sub(second_fee_bps));
+    let (a_in, a_out) = (U512::from(first.0), U512::from(first.1));
+    let (b_in, b_out) = (U512::from(second.0), U512::from(second.1));
+
+    // fee factors are scaled by d, so the reserve product is scaled by d^2 to match
+    let gains = ga.checked_mul(gb)?.checked_mul(a_out)?.checked_mul(b_out)?;
+    let reserves = d.checked_mul(d)?.checked_mul(a_in)?.checked_mul(b_in)?;
+    let denominator = d
+        .checked_mul(ga)?
+        .checked_mul(b_in)?
+        .checked_add(ga.checked_mul(gb)?.checked_mul(a_out)?)?;
+    if gains <= reserves || denominator.is_zero() {
+        return None;
+    }
+
+    let numerator = gains.checked_mul(reserves)?.integer_sqrt() - reserves;
+    U256::try_from(numerator / denominator)
+        .ok()
+        .filter(|amount| !amount.is_zero())
+}
@@ -0,0 +1,15 @@
+//! Optional log output for applications that do not set up `tracing` themselves
+//!
+//! The client only emits `tracing` spans and events. Applications that want them printed without
+//! configuring a subscriber can enable the `subscriber` feature and call [`init_subscriber`].
+use tracing_subscriber::{fmt, prelude::*, util::TryInitError, EnvFilter};
+
+/// Installs a global subscriber printing events to stdout, filtered by `RUST_LOG`.
+///
+/// Returns an error instead of panicking if a global subscriber is already installed.
+pub fn init_subscriber() -> Result<(), TryInitError> {
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(EnvFilter::from_default_env())
+        .try_init()
+}
@@ -0,0 +1,306 @@
+//! Helpers for working with the signed transactions carried in bundles
+use crate::types::{Bundle, BundleTx, MatchMakerNetwork};
+use ethers::{
+    signers::Signer,
+    types::{
+        transaction::{
+            eip2718::{TypedTransaction, TypedTransactionError},
+            eip2930::AccessList,
+        },
+        Address, Bytes, Eip1559TransactionRequest, Signature, SignatureError, H256, U256, U64,
+    },
+    utils::{
+        keccak256,
+        rlp::{DecoderError, Rlp, RlpStream},
+    },
+};
+use std::fmt;
+use tracing::{debug, instrument};
+
+/// EIP-2718 type byte of EIP-4844 blob transactions
+const BLOB_TX_TYPE: u8 = 0x03;
+
+/// Number of fields of a blob transaction covered by its signature
+const BLOB_TX_UNSIGNED_FIELDS: usize = 11;
+
+/// Reasons a signed transaction could not be decoded
+#[derive(Debug)]
+pub enum TxDecodeError {
+    /// The transaction bytes are empty
+    Empty,
+    /// The EIP-2718 transaction type is not supported
+    UnsupportedType(u8),
+    /// The RLP payload is malformed
+    Rlp(DecoderError),
+    /// The transaction could not be decoded by ethers
+    Transaction(TypedTransactionError),
+    /// The sender could not be recovered from the signature
+    Signature(SignatureError),
+}
+
+impl fmt::Display for TxDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "transaction bytes are empty"),
+            Self::UnsupportedType(tx_type) => {
+                write!(f, "unsupported transaction type 0x{tx_type:02x}")
+            }
+            Self::Rlp(err) => write!(f, "malformed transaction rlp: {err}"),
+            Self::Transaction(err) => write!(f, "failed to decode transaction: {err}"),
+            Self::Signature(err) => write!(f, "failed to recover sender: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TxDecodeError {}
+
+impl From<DecoderError> for TxDecodeError {
+    fn from(err: DecoderError) -> Self {
+        Self::Rlp(err)
+    }
+}
+
+impl From<TypedTransactionError> for TxDecodeError {
+    fn from(err: TypedTransactionError) -> Self {
+        Self::Transaction(err)
+    }
+}
+
+impl From<SignatureError> for TxDecodeError {
+    fn from(err: SignatureError) -> Self {
+        Self::Signature(err)
+    }
+}
+
+/// Blob fields of an EIP-4844 transaction, which have no counterpart in [`TypedTransaction`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobParams {
+    /// Maximum fee per blob gas the sender is willing to pay
+    pub max_fee_per_blob_gas: U256,
+    /// Versioned hashes of the blobs carried by the transaction
+    pub blob_versioned_hashes: Vec<H256>,
+}
+
+/// A signed transaction decoded from its raw bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedTransaction {
+    /// Transaction hash
+    pub hash: H256,
+    /// EIP-2718 transaction type, `0` for legacy transactions
+    pub tx_type: u8,
+    /// Sender recovered from the signature
+    pub from: Address,
+    /// Sender nonce
+    pub nonce: U256,
+    /// Gas limit
+    pub gas_limit: U256,
+    /// Gas price for legacy and EIP-2930 transactions, max fee per gas otherwise
+    pub max_fee_per_gas: U256,
+    /// Max priority fee per gas, if the transaction type has one
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// The decoded transaction with `from` populated.
+    ///
+    /// Blob transactions are represented by their EIP-1559 fields, see [`Self::blob`].
+    pub tx: TypedTransaction,
+    /// Signature of the transaction
+    pub signature: Signature,
+    /// Blob fields, only present for EIP-4844 transactions
+    pub blob: Option<BlobParams>,
+}
+
+/// Returns the hash of signed transaction bytes as accepted by `eth_sendRawTransaction`.
+///
+/// Blob transactions in their network encoding are hashed without their blobs, commitments and
+/// proofs, like in [`decode_signed_transaction`]. Malformed blob transactions are hashed as is.
+///
+/// * `raw` - Bytes of the signed transaction
+pub fn signed_transaction_hash(raw: &[u8]) -> H256 {
+    if let Some((&BLOB_TX_TYPE, payload)) = raw.split_first() {
+        if let Ok(tx) = Rlp::new(payload).at(0) {
+            if tx.is_list() {
+                return H256::from(keccak256([&[BLOB_TX_TYPE][..], tx.as_raw()].concat()));
+            }
+        }
+    }
+    H256::from(keccak256(raw))
+}
+
+/// Decodes signed transaction bytes as accepted by `eth_sendRawTransaction`.
+///
+/// Supports legacy, EIP-2930, EIP-1559 and EIP-4844 transactions. Blob transactions may be in
+/// either their canonical or network (with blobs, commitments and proofs) encoding.
+///
+/// * `raw` - Bytes of the signed transaction
+pub fn decode_signed_transaction(raw: &[u8]) -> Result<DecodedTransaction, TxDecodeError> {
+    let first = *raw.first().ok_or(TxDecodeError::Empty)?;
+    if first == BLOB_TX_TYPE {
+        return decode_blob_transaction(&raw[1..]);
+    }
+
+    let tx_type = match first {
+        0x01 | 0x02 => first,
+        // legacy transactions are rlp lists
+        0xc0..=0xff => 0x00,
+        _ => return Err(TxDecodeError::UnsupportedType(first)),
+    };
+
+    let (mut tx, signature) = TypedTransaction::decode_signed(&Rlp::new(raw))?;
+    let from = match tx.from() {
+        Some(from) => *from,
+        None => signature.recover(tx.sighash())?,
+    };
+    tx.set_from(from);
+
+    let (max_fee_per_gas, max_priority_fee_per_gas) = match &tx {
+        TypedTransaction::Eip1559(tx) => (
+            tx.max_fee_per_gas.unwrap_or_default(),
+            tx.max_priority_fee_per_gas,
+        ),
+        _ => (tx.gas_price().unwrap_or_default(), None),
+    };
+
+    Ok(DecodedTransaction {
+        hash: H256::from(keccak256(raw)),
+        tx_type,
+        from,
+        nonce: tx.nonce().copied().unwrap_or_default(),
+        gas_limit: tx.gas().copied().unwrap_or_default(),
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        tx,
+        signature,
+        blob: None,
+    })
+}
+
+/// Decodes an EIP-4844 transaction with the type byte stripped
+fn decode_blob_transaction(payload: &[u8]) -> Result<DecodedTransaction, TxDecodeError> {
+    let rlp = Rlp::new(payload);
+    // the network encoding wraps the transaction in a list with blobs, commitments and proofs
+    let rlp = if rlp.at(0)?.is_list() {
+        rlp.at(0)?
+    } else {
+        rlp
+    };
+
+    let mut unsigned = RlpStream::new_list(BLOB_TX_UNSIGNED_FIELDS);
+    for idx in 0..BLOB_TX_UNSIGNED_FIELDS {
+        unsigned.append_raw(rlp.at(idx)?.as_raw(), 1);
+    }
+    let sighash = H256::from(keccak256([&[BLOB_TX_TYPE][..], unsigned.as_raw()].concat()));
+
+    let signature = Signature {
+        v: rlp.val_at(11)?,
+        r: rlp.val_at(12)?,
+        s: rlp.val_at(13)?,
+    };
+    let from = signature.recover(sighash)?;
+
+    let chain_id: U64 = rlp.val_at(0)?;
+    let nonce: U256 = rlp.val_at(1)?;
+    let max_priority_fee_per_gas: U256 = rlp.val_at(2)?;
+    let max_fee_per_gas: U256 = rlp.val_at(3)?;
+    let gas_limit: U256 = rlp.val_at(4)?;
+    let to: Address = rlp.val_at(5)?;
+    let value: U256 = rlp.val_at(6)?;
+    let data = Bytes::from(rlp.at(7)?.data()?.to_vec());
+    let access_list: AccessList = rlp.val_at(8)?;
+
+    let tx = Eip1559TransactionRequest::new()
+        .from(from)
+        .to(to)
+        .nonce(nonce)
+        .gas(gas_limit)
+        .value(value)
+        .data(data)
+        .access_list(access_list)
+        .max_priority_fee_per_gas(max_priority_fee_per_gas)
+        .max_fee_per_gas(max_fee_per_gas)
+        .chain_id(chain_id);
+
+    Ok(DecodedTransaction {
+        hash: H256::from(keccak256([&[BLOB_TX_TYPE][..], rlp.as_raw()].concat())),
+        tx_type: BLOB_TX_TYPE,
+        from,
+        nonce,
+        gas_limit,
+        max_fee_per_gas,
+        max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+        tx: tx.into(),
+        signature,
+        blob: Some(BlobParams {
+            max_fee_per_blob_gas: rlp.val_at(9)?,
+            blob_versioned_hashes: rlp.list_at(10)?,
+        }),
+    })
+}
+
+impl BundleTx {
+    /// Decodes the signed transaction of this body entry.
+    ///
+    /// Returns `None` for tx hashes and nested bundles.
+    pub fn decode(&self) -> Result<Option<DecodedTransaction>, TxDecodeError> {
+        match self {
+            BundleTx::Tx { tx, .. } => decode_signed_transaction(tx).map(Some),
+            BundleTx::TxHash { .. } | BundleTx::Bundle { .. } => Ok(None),
+        }
+    }
+
+    /// Signs a transaction for the given network and wraps it in a bundle body entry.
+    ///
+    /// The chain ID of `tx` is set to the network's and `from` to the signer's address.
+    ///
+    /// * `signer` - Signer of the transaction, e.g. an ethers `LocalWallet`
+    /// * `network` - The network the bundle will be sent to
+    /// * `tx` - The transaction to sign
+    /// * `can_revert` - If true, the transaction can revert without the bundle being considered invalid
+    #[instrument(skip_all, fields(from = ?signer.address(), chain_id = network.chain_id))]
+    pub async fn sign<S: Signer>(
+        signer: &S,
+        network: &MatchMakerNetwork,
+        mut tx: TypedTransaction,
+        can_revert: bool,
+    ) -> Result<BundleTx, S::Error> {
+        tx.set_chain_id(network.chain_id);
+        tx.set_from(signer.address());
+        let signature = signer.sign_transaction(&tx).await?;
+        debug!(nonce = ?tx.nonce(), "Signed bundle transaction");
+
+        Ok(BundleTx::Tx {
+            tx: tx.rlp_signed(&signature),
+            can_revert,
+        })
+    }
+}
+
+impl Bundle {
+    /// Decodes all signed transactions of the bundle, including those of nested bundles, in body order.
+    pub fn decode_transactions(&self) -> Result<Vec<DecodedTransaction>, TxDecodeError> {
+        let mut decoded = Vec::new();
+        for tx in &self.body {
+            match tx {
+                BundleTx::Bundle { bundle } => decoded.extend(bundle.decode_transactions()?),
+                tx => decoded.extend(tx.decode()?),
+            }
+        }
+        Ok(decoded)
+    }
+
+    /// Signs a transaction with [`BundleTx::sign`] and appends it to the bundle body.
+    ///
+    /// * `signer` - Signer of the transaction, e.g. an ethers `LocalWallet`
+    /// * `network` - The network the bundle will be sent to
+    /// * `tx` - The transaction to sign
+    /// * `can_revert` - If true, the transaction can revert without the bundle being considered invalid
+    pub async fn push_signed<S: Signer>(
+        &mut self,
+        signer: &S,
+        network: &MatchMakerNetwork,
+        tx: TypedTransaction,
+        can_revert: bool,
+    ) -> Result<&mut Self, S::Error> {
+        let tx = BundleTx::sign(signer, network, tx, can_revert).await?;
+        self.body.push(tx);
+        Ok(self)
+    }
+}
@@ -1,82 +1,137 @@
 //! Types used by the Flashbot Matchmaker Client
-use ethers::types::{Address, Bytes, Chain, TxHash, U256, U64};
+use crate::transaction::signed_transaction_hash;
+use ethers::{
+    types::{Address, BlockNumber, Bytes, Chain, Log, TxHash, H256, U256, U64},
+    utils::keccak256,
+};
 use mev_share_rs::sse::{Event, EventTransaction, EventTransactionLog, FunctionSelector};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
-/// Network configuration for the supported networks
+/// Chain ID of the Ethereum Holesky testnet
+pub const HOLESKY_CHAIN_ID: u64 = 17000;
+
+/// Registry of the networks the client can connect to
+///
+/// Comes with the Flashbots Matchmakers predefined, additional networks such as a local
+/// Matchmaker can be added with [`SupportedNetworks::register`].
 #[derive(Debug, Clone, Default)]
-pub struct SupportedNetworks<'a> {
-    /// The supported networks
-    supported_networks: HashMap<String, MatchMakerNetwork<'a>>,
+pub struct SupportedNetworks {
+    /// The supported networks with unique names, in registration order
+    supported_networks: Vec<MatchMakerNetwork>,
 }
 
-impl<'a> SupportedNetworks<'a> {
+impl SupportedNetworks {
     /// Creates a new instance of SupportedNetworks with the predefined network configurations.
     pub fn new() -> Self {
-        let mut networks = HashMap::new();
-        networks.insert(
-            "mainnet".to_string(),
-            MatchMakerNetwork {
-                name: "mainnet",
-                chain_id: Chain::Mainnet.into(),
-                stream_url: "https://mev-share.flashbots.net",
-                api_url: "https://relay.flashbots.net",
-            },
-        );
-        networks.insert(
-            "goerli".to_string(),
-            MatchMakerNetwork {
-                name: "goerli",
-                chain_id: Chain::Goerli.into(),
-                stream_url: "https://mev-share-goerli.flashbots.net",
-                api_url: "https://relay-goerli.flashbots.net",
-            },
-        );
-
-        SupportedNetworks {
-            supported_networks: networks,
+        let mut networks = SupportedNetworks::default();
+        networks.register(MatchMakerNetwork {
+            name: "mainnet".to_string(),
+            chain_id: Chain::Mainnet.into(),
+            stream_url: "https://mev-share.flashbots.net".to_string(),
+            api_url: "https://relay.flashbots.net".to_string(),
+        });
+        networks.register(MatchMakerNetwork {
+            name: "goerli".to_string(),
+            chain_id: Chain::Goerli.into(),
+            stream_url: "https://mev-share-goerli.flashbots.net".to_string(),
+            api_url: "https://relay-goerli.flashbots.net".to_string(),
+        });
+        networks.register(MatchMakerNetwork {
+            name: "sepolia".to_string(),
+            chain_id: Chain::Sepolia.into(),
+            stream_url: "https://mev-share-sepolia.flashbots.net".to_string(),
+            api_url: "https://relay-sepolia.flashbots.net".to_string(),
+        });
+        networks.register(MatchMakerNetwork {
+            name: "holesky".to_string(),
+            chain_id: HOLESKY_CHAIN_ID,
+            stream_url: "https://mev-share-holesky.flashbots.net".to_string(),
+            api_url: "https://relay-holesky.flashbots.net".to_string(),
+        });
+
+        networks
+    }
+
+    /// Adds a network to the registry, replacing and returning any network with the same name.
+    /// A replaced network keeps its place in the registration order.
+    ///
+    /// * `network` - Configuration of the network, e.g. a local Matchmaker
+    pub fn register(&mut self, network: MatchMakerNetwork) -> Option<MatchMakerNetwork> {
+        match self
+            .supported_networks
+            .iter_mut()
+            .find(|registered| registered.name == network.name)
+        {
+            Some(registered) => Some(std::mem::replace(registered, network)),
+            None => {
+                self.supported_networks.push(network);
+                None
+            }
         }
     }
 
     /// Retrieves the configuration for the Ethereum mainnet.
     pub fn mainnet(&self) -> Option<&MatchMakerNetwork> {
-        self.supported_networks.get("mainnet")
+        self.get_by_name("mainnet")
     }
 
     /// Retrieves the configuration for the Ethereum Goerli testnet.
+    #[deprecated(note = "Goerli is deprecated, use `sepolia` or `holesky` instead")]
     pub fn goerli(&self) -> Option<&MatchMakerNetwork> {
-        self.supported_networks.get("goerli")
+        self.get_by_name("goerli")
     }
 
-    /// Checks if a network with the given chain ID is supported.
-    pub fn is_supported(&self, chain_id: u64) -> bool {
+    /// Retrieves the configuration for the Ethereum Sepolia testnet.
+    pub fn sepolia(&self) -> Option<&MatchMakerNetwork> {
+        self.get_by_name("sepolia")
+    }
+
+    /// Retrieves the configuration for the Ethereum Holesky testnet.
+    pub fn holesky(&self) -> Option<&MatchMakerNetwork> {
+        self.get_by_name("holesky")
+    }
+
+    /// Retrieves the network configuration registered under the given name.
+    pub fn get_by_name(&self, name: &str) -> Option<&MatchMakerNetwork> {
         self.supported_networks
-            .values()
-            .any(|network| network.chain_id == chain_id)
+            .iter()
+            .find(|network| network.name == name)
     }
 
     /// Retrieves the network configuration for the given chain ID.
-    pub fn get_network(&self, chain: u64) -> Option<MatchMakerNetwork<'a>> {
+    ///
+    /// If several registered networks share a chain ID, the one registered first is returned, so
+    /// the predefined networks take precedence over custom ones. Use [`Self::get_by_name`] to pick
+    /// another one.
+    pub fn get_by_chain_id(&self, chain_id: u64) -> Option<&MatchMakerNetwork> {
         self.supported_networks
-            .values()
-            .find(|network| network.chain_id == chain)
-            .cloned()
+            .iter()
+            .find(|network| network.chain_id == chain_id)
+    }
+
+    /// Checks if a network with the given chain ID is supported.
+    pub fn is_supported(&self, chain_id: u64) -> bool {
+        self.get_by_chain_id(chain_id).is_some()
+    }
+
+    /// Retrieves the network configuration for the given chain ID.
+    pub fn get_network(&self, chain: u64) -> Option<MatchMakerNetwork> {
+        self.get_by_chain_id(chain).cloned()
     }
 }
 
 /// Configuration used to connect to the Matchmaker
 #[derive(Deserialize, Debug, Serialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct MatchMakerNetwork<'a> {
+pub struct MatchMakerNetwork {
     /// Chain ID of network
     pub chain_id: u64,
     /// Lowercase name of network. e.g. "mainnet"
-    pub name: &'a str,
+    pub name: String,
     /// The URL of the Matchmaker API
-    pub stream_url: &'a str,
+    pub stream_url: String,
     /// Matchmaker bundle & transaction API URL
-    pub api_url: &'a str,
+    pub api_url: String,
 }
 
 /// Used to specify which type of event to listen for
@@ -119,7 +174,7 @@ pub struct InclusionParams {
 
 /// Transactions that make up the bundle. `hash` refers to a transaction hash from the Matchmaker event stream.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(untagged)]
 pub enum BundleTx {
     /// The hash of the transaction we are trying to backrun.
     TxHash {
@@ -132,8 +187,14 @@ pub enum BundleTx {
         /// Bytes of the signed transaction.
         tx: Bytes,
         /// If true, the transaction can revert without the bundle being considered invalid.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
         can_revert: bool,
     },
+    /// A nested bundle, e.g. the bundle being backrun.
+    Bundle {
+        /// The nested bundle.
+        bundle: Box<Bundle>,
+    },
 }
 
 /// Bundle privacy parameters
@@ -142,38 +203,40 @@ pub enum BundleTx {
 pub struct PrivacyParams {
     /// Data fields from bundle transactions to be shared with searchers on MEV-Share
     #[serde(skip_serializing_if = "Option::is_none")]
-    hints: Option<HintPreference>,
+    pub hints: Option<HintPreference>,
     /// Builders that are allowed to receive this bundle. See [mev-share spec](https://github.com/flashbots/mev-share/blob/main/builders/registration.json) for supported builders.
-    builders: Vec<String>,
+    pub builders: Vec<String>,
 }
 
 /// Conditions for receiving refunds
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Refund {
     /// Index of entry in `body` to which the refund percentage applies
-    body_idx: usize,
+    pub body_idx: usize,
     /// Minimum refund percentage required for this bundle to be eligible for use by another searcher
-    percent: u32,
+    pub percent: u32,
 }
 
 /// Specifies how refund should be paid if bundle is used by another searcher
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct RefundConfig {
     /// The address that receives this portion of the refund
-    address: String,
+    pub address: String,
     /// Percentage of refund to be paid to `address`. Set this to `100` unless splitting refunds between multiple recipients
-    percent: u32,
+    pub percent: u32,
 }
 
 /// Conditions for bundle to be considered for inclusion in a block, evaluated _after_ the bundle is placed in the block
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ValidityParams {
     /// Conditions for receiving refunds
     #[serde(skip_serializing_if = "Option::is_none")]
-    refund: Option<Vec<Refund>>,
+    pub refund: Option<Vec<Refund>>,
     /// Specifies how refund should be paid if bundle is used by another searcher
     #[serde(skip_serializing_if = "Option::is_none")]
-    refund_config: Option<Vec<RefundConfig>>,
+    pub refund_config: Option<Vec<RefundConfig>>,
 }
 
 /// Parameters sent to mev_sendBundle
@@ -191,63 +254,655 @@ pub struct Bundle {
     /// Bundle privacy parameters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub privacy: Option<PrivacyParams>,
+    /// UUID identifying the bundle for replacement and cancellation with `eth_cancelBundle`.
+    /// Ignored by endpoints that do not support cancellation.
+    #[serde(
+        rename = "replacementUuid",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub replacement_uuid: Option<String>,
+}
+
+impl BundleTx {
+    /// Returns the hash the Matchmaker uses for this body entry.
+    ///
+    /// Signed transactions are hashed with [`signed_transaction_hash`], which strips the blobs of
+    /// network encoded blob transactions, and nested bundles with [`Bundle::hash`].
+    pub fn hash(&self) -> H256 {
+        match self {
+            BundleTx::TxHash { hash } => *hash,
+            BundleTx::Tx { tx, .. } => signed_transaction_hash(tx),
+            BundleTx::Bundle { bundle } => bundle.hash(),
+        }
+    }
+}
+
+/// Generates a random version 4 UUID to use as a bundle's `replacement_uuid`
+pub fn random_replacement_uuid() -> String {
+    let mut bytes: [u8; 16] = ethers::core::rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+impl Bundle {
+    /// Computes the bundle hash the Matchmaker returns from `mev_sendBundle`.
+    ///
+    /// A bundle with a single body entry has the hash of that entry, otherwise the hash is the
+    /// keccak256 of the concatenated body entry hashes.
+    pub fn hash(&self) -> H256 {
+        if let [tx] = self.body.as_slice() {
+            return tx.hash();
+        }
+
+        let hashes: Vec<u8> = self
+            .body
+            .iter()
+            .flat_map(|tx| tx.hash().to_fixed_bytes())
+            .collect();
+        H256::from(keccak256(hashes))
+    }
 }
 
 /// Bundle details
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SendBundleResult {
     /// Bundle hash
-    pub bundle_hash: String,
+    pub bundle_hash: H256,
+}
+
+/// Parameters sent to eth_cancelPrivateTransaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelPrivateTransactionParams {
+    /// Hash of the private transaction to cancel
+    pub tx_hash: TxHash,
+}
+
+/// Parameters sent to eth_cancelBundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelBundleParams {
+    /// Replacement UUID the bundles to cancel were sent with
+    pub replacement_uuid: String,
+}
+
+/// Parameters sent to flashbots_getUserStatsV2
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStatsParams {
+    /// Current block number
+    pub block_number: U64,
+}
+
+/// Reputation and payment statistics of the searcher identified by the auth signer, returned by
+/// flashbots_getUserStatsV2
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStats {
+    /// Whether the searcher has high priority for bundle simulation
+    pub is_high_priority: bool,
+    /// Total validator payments of the searcher's bundles, in wei
+    #[serde(with = "decimal_u256")]
+    pub all_time_validator_payments: U256,
+    /// Total gas simulated for the searcher's bundles
+    #[serde(with = "decimal_u256")]
+    pub all_time_gas_simulated: U256,
+    /// Validator payments of the searcher's bundles in the last 7 days, in wei
+    #[serde(rename = "last7dValidatorPayments", with = "decimal_u256")]
+    pub last_7d_validator_payments: U256,
+    /// Gas simulated for the searcher's bundles in the last 7 days
+    #[serde(rename = "last7dGasSimulated", with = "decimal_u256")]
+    pub last_7d_gas_simulated: U256,
+    /// Validator payments of the searcher's bundles in the last day, in wei
+    #[serde(rename = "last1dValidatorPayments", with = "decimal_u256")]
+    pub last_1d_validator_payments: U256,
+    /// Gas simulated for the searcher's bundles in the last day
+    #[serde(rename = "last1dGasSimulated", with = "decimal_u256")]
+    pub last_1d_gas_simulated: U256,
+}
+
+/// Parameters sent to flashbots_getBundleStatsV2
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStatsParams {
+    /// Bundle hash returned by mev_sendBundle
+    pub bundle_hash: H256,
+    /// Block number the bundle was sent for
+    pub block_number: U64,
+}
+
+/// Time a builder considered or sealed a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderTimestamp {
+    /// BLS public key of the builder
+    pub pubkey: String,
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+}
+
+/// Simulation and builder statistics of a bundle, returned by flashbots_getBundleStatsV2
+///
+/// Timestamps are RFC 3339 strings, e.g. `2022-10-06T21:36:06.317Z`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStats {
+    /// Whether the bundle was simulated
+    pub is_simulated: bool,
+    /// Whether the bundle was simulated with high priority
+    pub is_high_priority: bool,
+    /// When the bundle was simulated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulated_at: Option<String>,
+    /// When the relay received the bundle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<String>,
+    /// Builders that considered the bundle for a block, and when
+    #[serde(default)]
+    pub considered_by_builders_at: Vec<BuilderTimestamp>,
+    /// Builders that sealed a block containing the bundle, and when
+    #[serde(default)]
+    pub sealed_by_builders_at: Vec<BuilderTimestamp>,
+}
+
+/// Parameters sent to eth_sendBundle, the classic Flashbots bundle API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlashbotsBundle {
+    /// Signed transactions to execute in order
+    pub txs: Vec<Bytes>,
+    /// Block number the bundle is valid for
+    pub block_number: U64,
+    /// Minimum timestamp the bundle is valid for, in seconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_timestamp: Option<u64>,
+    /// Maximum timestamp the bundle is valid for, in seconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_timestamp: Option<u64>,
+    /// Hashes of transactions in `txs` that are allowed to revert
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reverting_tx_hashes: Vec<TxHash>,
+    /// UUID that can be used to replace or cancel the bundle with eth_cancelBundle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement_uuid: Option<String>,
+    /// Builders the bundle is shared with, in addition to Flashbots
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builders: Vec<String>,
+}
+
+/// Parameters sent to eth_callBundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleParams {
+    /// Signed transactions to simulate in order
+    pub txs: Vec<Bytes>,
+    /// Block number the bundle is simulated for
+    pub block_number: U64,
+    /// Block whose state the simulation is based on, e.g. `BlockNumber::Latest`
+    pub state_block_number: BlockNumber,
+    /// Timestamp of the simulated block, defaults to the state block's timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+}
+
+/// Simulation result of a single transaction of an eth_callBundle request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleTxResult {
+    /// Hash of the transaction
+    pub tx_hash: TxHash,
+    /// Sender of the transaction
+    pub from_address: Address,
+    /// Recipient of the transaction, `None` for contract creations
+    #[serde(default)]
+    pub to_address: Option<Address>,
+    /// Gas used by the transaction
+    pub gas_used: u64,
+    /// Effective gas price of the transaction, in wei
+    #[serde(with = "decimal_u256")]
+    pub gas_price: U256,
+    /// Gas fees paid to the coinbase, in wei
+    #[serde(with = "decimal_u256")]
+    pub gas_fees: U256,
+    /// Balance change of the coinbase, in wei
+    #[serde(with = "decimal_u256")]
+    pub coinbase_diff: U256,
+    /// Ether transferred to the coinbase directly, in wei
+    #[serde(with = "decimal_u256")]
+    pub eth_sent_to_coinbase: U256,
+    /// Return data of the transaction
+    #[serde(default)]
+    pub value: Option<Bytes>,
+    /// Error the transaction failed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Revert reason of the transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert: Option<String>,
+}
+
+impl CallBundleTxResult {
+    /// Returns true if the transaction neither failed nor reverted
+    pub fn is_success(&self) -> bool {
+        self.error.is_none() && self.revert.is_none()
+    }
+}
+
+/// Simulation result of an eth_callBundle request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBundleResponse {
+    /// Hash of the simulated bundle
+    pub bundle_hash: H256,
+    /// Effective gas price of the bundle, in wei
+    #[serde(with = "decimal_u256")]
+    pub bundle_gas_price: U256,
+    /// Balance change of the coinbase, in wei
+    #[serde(with = "decimal_u256")]
+    pub coinbase_diff: U256,
+    /// Ether transferred to the coinbase directly, in wei
+    #[serde(with = "decimal_u256")]
+    pub eth_sent_to_coinbase: U256,
+    /// Gas fees paid to the coinbase, in wei
+    #[serde(with = "decimal_u256")]
+    pub gas_fees: U256,
+    /// Per-transaction results, in bundle order
+    pub results: Vec<CallBundleTxResult>,
+    /// Block whose state the simulation was based on
+    pub state_block_number: u64,
+    /// Gas used by all transactions of the bundle
+    pub total_gas_used: u64,
+}
+
+impl CallBundleResponse {
+    /// Returns true if all transactions of the bundle neither failed nor reverted
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(CallBundleTxResult::is_success)
+    }
+}
+
+/// Overrides of the simulated block for mev_simBundle, unset fields are taken from the block
+/// after `parent_block`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBundleOverrides {
+    /// Block whose state the simulation is based on, defaults to the bundle's target block - 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_block: Option<U64>,
+    /// Number of the simulated block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<U64>,
+    /// Coinbase of the simulated block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coinbase: Option<Address>,
+    /// Timestamp of the simulated block, in seconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Gas limit of the simulated block
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    /// Base fee of the simulated block, in wei
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee: Option<U256>,
+    /// Timeout of the simulation in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// Logs emitted by a simulated bundle body entry, either a transaction or a nested bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBundleLogs {
+    /// Logs of a transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_logs: Option<Vec<Log>>,
+    /// Logs of the entries of a nested bundle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_logs: Option<Vec<SimBundleLogs>>,
+}
+
+/// Simulation result of a mev_simBundle request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBundleResponse {
+    /// Whether the bundle would be included
+    pub success: bool,
+    /// Reason the simulation failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Block whose state the simulation was based on
+    pub state_block: U64,
+    /// Profit of the bundle divided by the gas it used, in wei
+    pub mev_gas_price: U256,
+    /// Balance change of the coinbase, in wei
+    pub profit: U256,
+    /// Part of the profit that can be refunded to the users of the bundle, in wei
+    pub refundable_value: U256,
+    /// Gas used by the bundle
+    pub gas_used: U64,
+    /// Logs emitted by the bundle body, in bundle order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs: Option<Vec<SimBundleLogs>>,
+}
+
+/// Preferences of a private transaction sent with eth_sendPrivateTransaction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivateTxPreferences {
+    /// Send the transaction to all registered builders right away
+    pub fast: bool,
+    /// Hints shared with searchers and builders the transaction is sent to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacyParams>,
+}
+
+/// Parameters sent to eth_sendPrivateTransaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendPrivateTransactionParams {
+    /// Bytes of the signed transaction
+    pub tx: Bytes,
+    /// Last block the transaction can be included in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_block_number: Option<U64>,
+    /// Privacy and builder preferences of the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferences: Option<PrivateTxPreferences>,
+}
+
+impl SendPrivateTransactionParams {
+    /// Creates the parameters of a private transaction, see [`TransactionOptions`]
+    ///
+    /// * `tx` - Bytes of the signed transaction
+    /// * `options` - Hints, builders and maximum block of the transaction
+    pub fn new(tx: Bytes, options: &TransactionOptions) -> Self {
+        let privacy =
+            (options.hints.is_some() || options.builders.is_some()).then(|| PrivacyParams {
+                hints: options.hints.clone(),
+                builders: options.builders.clone().unwrap_or_default(),
+            });
+
+        Self {
+            tx,
+            max_block_number: options.max_block_number,
+            preferences: Some(PrivateTxPreferences {
+                fast: true,
+                privacy,
+            }),
+        }
+    }
+}
+
+/// Filters of the event history, see [`MatchmakerClient::get_event_history`](crate::client::MatchmakerClient::get_event_history)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventHistoryParams {
+    /// First block of the returned events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_start: Option<u64>,
+    /// Last block of the returned events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_end: Option<u64>,
+    /// Earliest timestamp of the returned events, in seconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_start: Option<u64>,
+    /// Latest timestamp of the returned events, in seconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_end: Option<u64>,
+    /// Maximum number of events to return, capped at the `max_limit` of [`EventHistoryInfo`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// Number of matching events to skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl EventHistoryParams {
+    /// Returns the filters as a URL query string, without the leading `?`
+    pub fn to_query(&self) -> String {
+        [
+            ("blockStart", self.block_start),
+            ("blockEnd", self.block_end),
+            ("timestampStart", self.timestamp_start),
+            ("timestampEnd", self.timestamp_end),
+            ("limit", self.limit),
+            ("offset", self.offset),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| format!("{name}={value}")))
+        .collect::<Vec<_>>()
+        .join("&")
+    }
+}
+
+/// Range and size of the event history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventHistoryInfo {
+    /// Number of events in the history
+    pub count: u64,
+    /// Block of the oldest event
+    pub min_block: u64,
+    /// Block of the newest event
+    pub max_block: u64,
+    /// Timestamp of the oldest event
+    pub min_timestamp: u64,
+    /// Timestamp of the newest event
+    pub max_timestamp: u64,
+    /// Maximum number of events returned by a single history request
+    pub max_limit: u64,
+}
+
+/// A transaction shared in a historic hint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryHintTx {
+    /// Transaction recipient address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Calldata of the transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_data: Option<Bytes>,
+    /// 4-byte function selector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_selector: Option<Bytes>,
+}
+
+/// The hint of a historic event, as it was shared on the event stream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryHint {
+    /// Transaction or bundle hash
+    pub hash: H256,
+    /// Transactions of the transaction or bundle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub txs: Option<Vec<HistoryHintTx>>,
+    /// Logs emitted by the transaction or bundle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs: Option<Vec<EventTransactionLog>>,
+    /// Gas used by the transaction or bundle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<U64>,
+    /// Change in coinbase value divided by gas used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mev_gas_price: Option<U256>,
+}
+
+/// An event of the event history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventHistory {
+    /// Block the event was shared in
+    pub block: u64,
+    /// Time the event was shared, in seconds since the epoch
+    pub timestamp: u64,
+    /// The shared hint
+    pub hint: HistoryHint,
+}
+
+/// (De)serializes a [`U256`] as a decimal string, as returned by the Flashbots stats and
+/// simulation endpoints
+mod decimal_u256 {
+    use ethers::types::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<U256, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        U256::from_dec_str(&value).map_err(D::Error::custom)
+    }
 }
 
 /// Response received from matchmaker API
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SendBundleResponse {
     /// Bundle hash
-    pub bundle_hash: String,
+    pub bundle_hash: H256,
 }
 
 /// Decodes a raw sendBundle response
 impl SendBundleResult {
     pub fn from_response(response: &SendBundleResponse) -> Self {
         SendBundleResult {
-            bundle_hash: response.bundle_hash.clone(),
+            bundle_hash: response.bundle_hash,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-/// Parameters accepted by the [send_transaction] function
+/// Parameters accepted by [`MatchmakerClient::send_private_transaction`](crate::client::MatchmakerClient::send_private_transaction)
 pub struct TransactionOptions {
     /// Hints define what data about a transaction is shared with searchers
     #[serde(skip_serializing_if = "Option::is_none")]
-    hints: Option<HintPreference>,
+    pub hints: Option<HintPreference>,
     /// Maximum block number for the transaction to be included in
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_block_number: Option<U64>,
+    pub max_block_number: Option<U64>,
+    /// Builders the transaction is sent to, in addition to Flashbots
     #[serde(skip_serializing_if = "Option::is_none")]
-    builders: Option<Vec<String>>,
+    pub builders: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+/// Data shared with searchers about a bundle or transaction
+///
+/// Sent as the list of shared hints, e.g. `["calldata", "logs"]`, only hints set to `Some(true)` are
+/// shared. Configuration files may also use a table of flags, e.g. `{ calldata = true }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "HintPreferenceRepr", into = "Vec<String>")]
 pub struct HintPreference {
     /// Share the calldata of the transaction
-    #[serde(skip_serializing_if = "Option::is_none")]
-    calldata: Option<bool>,
+    pub calldata: Option<bool>,
     /// Share the contract address of the transaction
-    #[serde(skip_serializing_if = "Option::is_none")]
-    contract_address: Option<bool>,
+    pub contract_address: Option<bool>,
     /// Share the 4byte function selector of the transaction
-    #[serde(skip_serializing_if = "Option::is_none")]
-    function_selector: Option<bool>,
+    pub function_selector: Option<bool>,
     /// Share the logs emitted by the transaction
-    #[serde(skip_serializing_if = "Option::is_none")]
-    logs: Option<bool>,
+    pub logs: Option<bool>,
     /// Share tx hashes of transactions in bundle
-    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<bool>,
+    /// Share the hash of the transaction or bundle
+    pub hash: Option<bool>,
+}
+
+impl HintPreference {
+    /// Names of the shared hints, in the order they are sent
+    pub fn shared(&self) -> Vec<&'static str> {
+        let hints = [
+            ("calldata", self.calldata),
+            ("contract_address", self.contract_address),
+            ("function_selector", self.function_selector),
+            ("logs", self.logs),
+            ("tx_hash", self.tx_hash),
+            ("hash", self.hash),
+        ];
+        hints
+            .into_iter()
+            .filter(|(_, shared)| *shared == Some(true))
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+impl From<HintPreference> for Vec<String> {
+    fn from(hints: HintPreference) -> Self {
+        hints.shared().into_iter().map(String::from).collect()
+    }
+}
+
+/// Accepted forms of [`HintPreference`]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HintPreferenceRepr {
+    List(Vec<String>),
+    Flags(HintFlags),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HintFlags {
+    calldata: Option<bool>,
+    contract_address: Option<bool>,
+    function_selector: Option<bool>,
+    logs: Option<bool>,
     tx_hash: Option<bool>,
+    hash: Option<bool>,
+}
+
+impl TryFrom<HintPreferenceRepr> for HintPreference {
+    type Error = String;
+
+    fn try_from(repr: HintPreferenceRepr) -> Result<Self, Self::Error> {
+        match repr {
+            HintPreferenceRepr::List(names) => {
+                let mut hints = HintPreference::default();
+                for name in names {
+                    let hint = match name.as_str() {
+                        "calldata" => &mut hints.calldata,
+                        "contract_address" => &mut hints.contract_address,
+                        "function_selector" => &mut hints.function_selector,
+                        "logs" => &mut hints.logs,
+                        "tx_hash" => &mut hints.tx_hash,
+                        "hash" => &mut hints.hash,
+                        _ => return Err(format!("unknown hint `{name}`")),
+                    };
+                    *hint = Some(true);
+                }
+                Ok(hints)
+            }
+            HintPreferenceRepr::Flags(HintFlags {
+                calldata,
+                contract_address,
+                function_selector,
+                logs,
+                tx_hash,
+                hash,
+            }) => Ok(HintPreference {
+                calldata,
+                contract_address,
+                function_selector,
+                logs,
+                tx_hash,
+                hash,
+            }),
+        }
+    }
 }
 
 //////////////////////// Matchmaker Event Types ////////////////////////
@@ -380,7 +1035,19 @@ impl From<&Event> for PendingBundle {
 }
 
 /// Pending transaction or bundle from the matchmaker stream
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum PendingTxOrBundle {
     Tx(PendingTransaction),
     Bundle(PendingBundle),
 }
+
+impl PendingTxOrBundle {
+    /// Returns the hash of the pending transaction or bundle
+    pub fn hash(&self) -> TxHash {
+        match self {
+            PendingTxOrBundle::Tx(tx) => tx.hash,
+            PendingTxOrBundle::Bundle(bundle) => bundle.hash,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Client-side checks for bundles before they are sent to the Matchmaker
+//!
+//! These mirror the structural checks the Matchmaker performs on `mev_sendBundle`, so malformed
+//! bundles can be rejected locally instead of costing a round trip.
+use crate::types::{Bundle, BundleTx};
+use ethers::types::U64;
+use std::fmt;
+
+/// Maximum nesting level of bundles accepted by the Matchmaker
+pub const MAX_NESTING_DEPTH: usize = 1;
+
+/// Maximum number of entries in a bundle body
+pub const MAX_BODY_SIZE: usize = 50;
+
+/// Reasons a bundle can be rejected before submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleValidationError {
+    /// The bundle body contains no transactions
+    EmptyBody,
+    /// The bundle body contains more entries than the Matchmaker accepts
+    BodyTooLarge {
+        /// Number of entries in the body
+        len: usize,
+    },
+    /// A refund references an entry outside of the bundle body
+    RefundIndexOutOfRange {
+        /// The offending `body_idx`
+        body_idx: usize,
+        /// Number of entries in the body
+        body_len: usize,
+    },
+    /// A refund percentage is above 100
+    RefundPercentTooHigh {
+        /// The offending percentage
+        percent: u32,
+    },
+    /// The refund config percentages do not add up to 100
+    RefundConfigPercentSum {
+        /// Sum of all refund config percentages
+        total: u32,
+    },
+    /// `max_block` is lower than the target `block`
+    MaxBlockBeforeBlock {
+        /// Target block number
+        block: U64,
+        /// Maximum block number
+        max_block: U64,
+    },
+    /// Bundles are nested deeper than the Matchmaker accepts
+    NestingTooDeep {
+        /// Depth at which the nested bundle was found
+        depth: usize,
+    },
+}
+
+impl fmt::Display for BundleValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyBody => write!(f, "bundle body is empty"),
+            Self::BodyTooLarge { len } => write!(
+                f,
+                "bundle body has {len} entries, at most {MAX_BODY_SIZE} are allowed"
+            ),
+            Self::RefundIndexOutOfRange { body_idx, body_len } => write!(
+                f,
+                "refund body_idx {body_idx} is out of range for a body of {body_len} entries"
+            ),
+            Self::RefundPercentTooHigh { percent } => {
+                write!(f, "refund percent {percent} is above 100")
+            }
+            Self::RefundConfigPercentSum { total } => {
+                write!(f, "refund config percents sum to {total}, expected 100")
+            }
+            Self::MaxBlockBeforeBlock { block, max_block } => {
+                write!(f, "max_block {max_block} is lower than block {block}")
+            }
+            Self::NestingTooDeep { depth } => write!(
+                f,
+                "bundle nested at depth {depth}, at most {MAX_NESTING_DEPTH} is allowed"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BundleValidationError {}
+
+impl Bundle {
+    /// Checks the bundle, including any nested bundles, for errors the Matchmaker would reject it for
+    pub fn validate(&self) -> Result<(), BundleValidationError> {
+        self.validate_at_depth(0)
+    }
+
+    fn validate_at_depth(&self, depth: usize) -> Result<(), BundleValidationError> {
+        if depth > MAX_NESTING_DEPTH {
+            return Err(BundleValidationError::NestingTooDeep { depth });
+        }
+
+        if self.body.is_empty() {
+            return Err(BundleValidationError::EmptyBody);
+        }
+        if self.body.len() > MAX_BODY_SIZE {
+            return Err(BundleValidationError::BodyTooLarge {
+                len: self.body.len(),
+            });
+        }
+
+        if let Some(max_block) = self.inclusion.max_block {
+            if max_block < self.inclusion.block {
+                return Err(BundleValidationError::MaxBlockBeforeBlock {
+                    block: self.inclusion.block,
+                    max_block,
+                });
+            }
+        }
+
+        if let Some(validity) = &self.validity {
+            for refund in validity.refund.iter().flatten() {
+                if refund.body_idx >= self.body.len() {
+                    return Err(BundleValidationError::RefundIndexOutOfRange {
+                        body_idx: refund.body_idx,
+                        body_len: self.body.len(),
+                    });
+                }
+                if refund.percent > 100 {
+                    return Err(BundleValidationError::RefundPercentTooHigh {
+                        percent: refund.percent,
+                    });
+                }
+            }
+
+            if let Some(refund_config) = &validity.refund_config {
+                let total: u32 = refund_config.iter().map(|config| config.percent).sum();
+                if total != 100 {
+                    return Err(BundleValidationError::RefundConfigPercentSum { total });
+                }
+            }
+        }
+
+        for tx in &self.body {
+            if let BundleTx::Bundle { bundle } = tx {
+                bundle.validate_at_depth(depth + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use ethers::{types::H256, utils::keccak256};
+use mev_share_client::types::{BundleTx, SendBundleResponse};
+use spec::spec_bundle;
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+#[test]
+fn single_tx_bundle_hash_is_tx_hash() {
+    let bundle = spec_bundle("simple bundle");
+    let BundleTx::Tx { tx, .. } = &bundle.body[0] else {
+        panic!("expected a signed tx");
+    };
+    assert_eq!(bundle.hash(), H256::from(keccak256(tx)));
+}
+
+#[test]
+fn bundle_hash_covers_nested_bundles() {
+    let bundle = spec_bundle("bundle with backrun of bundle");
+    let BundleTx::Bundle { bundle: inner } = &bundle.body[0] else {
+        panic!("expected a nested bundle");
+    };
+
+    // keccak256 of the concatenated body hashes, computed outside of this crate
+    let inner_hash: H256 = "0xffc34664c7fa11717e9a76216f97636d14c4aa2df0c90e79654d8a8bc14dd0b6"
+        .parse()
+        .unwrap();
+    let bundle_hash: H256 = "0xf91f8c441663376ae9ec9a0fca4d72f94e2fd8a3442bc8f49871070ba60a98ef"
+        .parse()
+        .unwrap();
+    assert_eq!(inner.hash(), inner_hash);
+    assert_eq!(bundle.hash(), bundle_hash);
+}
+
+#[test]
+fn decodes_send_bundle_response() {
+    let response: SendBundleResponse = serde_json::from_str(
+        r#"{"bundleHash":"0x2dc8b0bd8ab5a2c0b5b0b1e1e0eab8c1ee4ab2f06c5a1ab0d4d1c6a4f4d1e0b1"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        response.bundle_hash,
+        "0x2dc8b0bd8ab5a2c0b5b0b1e1e0eab8c1ee4ab2f06c5a1ab0d4d1c6a4f4d1e0b1"
+            .parse()
+            .unwrap()
+    );
+}
@@ -0,0 +1,74 @@
+use ethers::types::TxHash;
+use fixture::client;
+use mev_share_client::types::{random_replacement_uuid, Bundle};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const TX_HASH: &str = "0x2dc8b0bd8ab5a2c0b5b0b1e1e0eab8c1ee4ab2f06c5a1ab0d4d1c6a4f4d1e0b1";
+
+#[tokio::test]
+async fn cancels_private_transaction() {
+    let server = MockServer::spawn(vec![Reply::Result(json!(true))]).await;
+    let tx_hash: TxHash = TX_HASH.parse().unwrap();
+
+    assert!(client(&server)
+        .cancel_private_transaction(tx_hash)
+        .await
+        .unwrap());
+
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "eth_cancelPrivateTransaction");
+    assert_eq!(request["params"], json!([{ "txHash": TX_HASH }]));
+}
+
+#[tokio::test]
+async fn cancels_bundle_by_replacement_uuid() {
+    let server = MockServer::spawn(vec![Reply::Result(json!(null))]).await;
+    let uuid = random_replacement_uuid();
+
+    client(&server).cancel_bundle(&uuid).await.unwrap();
+
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "eth_cancelBundle");
+    assert_eq!(request["params"], json!([{ "replacementUuid": uuid }]));
+}
+
+#[tokio::test]
+async fn reports_cancellation_errors() {
+    let server = MockServer::spawn(vec![Reply::Error(-32601)]).await;
+
+    assert!(client(&server).cancel_bundle("unknown").await.is_err());
+}
+
+#[test]
+fn serializes_replacement_uuid_only_when_set() {
+    let mut bundle = Bundle::default();
+    assert!(serde_json::to_value(&bundle).unwrap()["replacementUuid"].is_null());
+
+    bundle.replacement_uuid = Some(random_replacement_uuid());
+    let value = serde_json::to_value(&bundle).unwrap();
+    assert_eq!(
+        value["replacementUuid"].as_str(),
+        bundle.replacement_uuid.as_deref()
+    );
+}
+
+#[test]
+fn generates_v4_uuids() {
+    let uuid = random_replacement_uuid();
+    let groups: Vec<_> = uuid.split('-').map(str::len).collect();
+    assert_eq!(groups, [8, 4, 4, 4, 12]);
+    assert_eq!(&uuid[14..15], "4");
+    assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+    assert_ne!(uuid, random_replacement_uuid());
+}
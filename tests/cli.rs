@@ -0,0 +1,218 @@
+#![cfg(feature = "cli")]
+
+use ethers::types::{Address, Bytes, H256};
+use fixture::KEY;
+use mev_share_client::{
+    fixtures::SpecFixture,
+    types::{PendingTransaction, PendingTxOrBundle},
+};
+use mev_share_rs::sse::{EventTransactionLog, FunctionSelector};
+use mock_server::{MockServer, Reply};
+use serde_json::{json, Value};
+use std::{
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const ROUTER: Address = Address::repeat_byte(0x11);
+const SWAP_TOPIC: H256 = H256::repeat_byte(0x22);
+
+/// The CLI, without any `MEV_SHARE_` variables of the environment
+fn command(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mev-share"));
+    command.args(args).env_clear();
+    command
+}
+
+/// Runs the CLI against `server` without an auth key
+async fn mev_share(server: &MockServer, args: &[&str]) -> Output {
+    let mut command = command(args);
+    command
+        .env("MEV_SHARE_NETWORK", "local")
+        .env("MEV_SHARE_CHAIN_ID", "31337")
+        .env("MEV_SHARE_STREAM_URL", &server.url)
+        .env("MEV_SHARE_API_URL", &server.url);
+    tokio::task::spawn_blocking(move || command.output().unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn authenticated_commands_require_an_auth_key() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({}))]).await;
+    let bundle_hash = format!("{:?}", ethers::types::H256::zero());
+
+    for args in [
+        &["stats", "user", "--block", "1"][..],
+        &["stats", "bundle", &bundle_hash, "--block", "1"],
+        &["send-tx", "0x02"],
+    ] {
+        let output = mev_share(&server, args).await;
+
+        assert!(!output.status.success(), "{args:?}");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains("no auth key configured"),
+            "{args:?}: {stderr}"
+        );
+    }
+    assert!(server.received().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn read_only_commands_run_without_an_auth_key() {
+    let server = MockServer::spawn(vec![Reply::Json(json!({
+        "count": 100,
+        "minBlock": 9000000,
+        "maxBlock": 9134283,
+        "minTimestamp": 1686000000,
+        "maxTimestamp": 1686847200,
+        "maxLimit": 500
+    }))])
+    .await;
+
+    let output = mev_share(&server, &["history", "--info"]).await;
+
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["count"], 100);
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mev-share-cli-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn hint(to: Address, selector: Option<[u8; 4]>, topics: Vec<H256>) -> PendingTxOrBundle {
+    let logs = (!topics.is_empty()).then(|| {
+        vec![EventTransactionLog {
+            address: to,
+            topics,
+            data: Bytes::default(),
+        }]
+    });
+    PendingTxOrBundle::Tx(PendingTransaction::new(
+        H256::random(),
+        logs,
+        Some(to),
+        selector.map(FunctionSelector),
+        None,
+        None,
+        None,
+    ))
+}
+
+#[test]
+fn replay_prints_hints_matching_all_filters() {
+    let swap = hint(ROUTER, Some([0x12, 0x34, 0x56, 0x78]), vec![SWAP_TOPIC]);
+    let transfer = hint(Address::repeat_byte(0x33), None, Vec::new());
+    let lines: Vec<String> = [&swap, &transfer]
+        .iter()
+        .map(|hint| serde_json::to_string(hint).unwrap())
+        .collect();
+    let file = temp_file("hints.jsonl", &lines.join("\n"));
+    let file = file.to_str().unwrap();
+
+    let router = format!("{ROUTER:?}");
+    let topic = format!("{SWAP_TOPIC:?}");
+    let other_topic = format!("{:?}", H256::repeat_byte(0x44));
+    let transfer_to = format!("{:?}", Address::repeat_byte(0x33));
+    for (filter, expected) in [
+        (vec![], vec![swap.hash(), transfer.hash()]),
+        (vec!["--to", &router], vec![swap.hash()]),
+        (
+            vec!["--to", &transfer_to, "--to", &router],
+            vec![swap.hash(), transfer.hash()],
+        ),
+        (vec!["--selector", "0x12345678"], vec![swap.hash()]),
+        (vec!["--selector", "0xa9059cbb"], vec![]),
+        (vec!["--topic", &topic], vec![swap.hash()]),
+        (vec!["--with-logs"], vec![swap.hash()]),
+        (vec!["--to", &router, "--topic", &other_topic], vec![]),
+    ] {
+        let mut args = vec!["replay", file, "--output", "json"];
+        args.extend(&filter);
+        let output = command(&args).output().unwrap();
+
+        assert!(output.status.success(), "{filter:?}");
+        let printed: Vec<H256> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<PendingTxOrBundle>(line)
+                    .unwrap()
+                    .hash()
+            })
+            .collect();
+        assert_eq!(printed, expected, "{filter:?}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn loads_bundles_and_spec_test_cases() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({
+        "success": true,
+        "stateBlock": "0x1",
+        "mevGasPrice": "0x0",
+        "profit": "0x0",
+        "refundableValue": "0x0",
+        "gasUsed": "0x0"
+    }))])
+    .await;
+    let test = &SpecFixture::spec().tests[0];
+    let bundle = serde_json::to_value(&test.bundle).unwrap();
+    let bundle_file = temp_file("bundle.json", &bundle.to_string());
+    let test_file = temp_file(
+        "test.json",
+        &json!({ "name": test.name, "bundle": bundle }).to_string(),
+    );
+
+    for file in [&bundle_file, &test_file] {
+        let mut command = command(&["sim-bundle", file.to_str().unwrap(), "--block", "5"]);
+        command
+            .env("MEV_SHARE_NETWORK", "local")
+            .env("MEV_SHARE_CHAIN_ID", "31337")
+            .env("MEV_SHARE_STREAM_URL", &server.url)
+            .env("MEV_SHARE_API_URL", &server.url)
+            .env("MEV_SHARE_AUTH_KEY", KEY);
+        let output = tokio::task::spawn_blocking(move || command.output().unwrap())
+            .await
+            .unwrap();
+        assert!(output.status.success(), "{file:?}");
+    }
+
+    let mut expected = bundle;
+    expected["inclusion"]["block"] = json!("0x5");
+    let received: Vec<Value> = server
+        .received()
+        .iter()
+        .map(|request| request["params"][0].clone())
+        .collect();
+    assert_eq!(received, [expected.clone(), expected]);
+}
+
+#[test]
+fn rejects_invalid_bundle_files() {
+    let file = temp_file("invalid.json", r#"{ "name": "no bundle" }"#);
+
+    let output = command(&["send-bundle", file.to_str().unwrap()])
+        .env("MEV_SHARE_AUTH_KEY", KEY)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("invalid bundle"));
+}
@@ -0,0 +1,53 @@
+use ethers::signers::LocalWallet;
+use mev_share_client::{
+    client::MatchmakerClient,
+    types::{MatchMakerNetwork, StreamingEventTypes},
+};
+
+fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+
+fn local_client() -> MatchmakerClient<LocalWallet> {
+    client("http://127.0.0.1:8080", "http://127.0.0.1:8545")
+}
+
+fn client(stream_url: &str, api_url: &str) -> MatchmakerClient<LocalWallet> {
+    let network = MatchMakerNetwork {
+        chain_id: 31337,
+        name: "local".to_string(),
+        stream_url: stream_url.to_string(),
+        api_url: api_url.to_string(),
+    };
+    let wallet: LocalWallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        .parse()
+        .unwrap();
+    MatchmakerClient::use_network(wallet, network)
+}
+
+#[test]
+fn client_is_shareable_across_tasks() {
+    assert_shareable::<MatchmakerClient<LocalWallet>>();
+}
+
+#[test]
+fn clones_share_network() {
+    let client = local_client();
+    let clone = client.clone();
+    assert_eq!(clone.network().name, "local");
+    assert_eq!(clone.network().api_url, client.network().api_url);
+}
+
+#[tokio::test]
+async fn fails_to_listen_to_an_unreachable_stream() {
+    let client = client("http://127.0.0.1:1", "http://127.0.0.1:1");
+
+    let result = client
+        .on(StreamingEventTypes::Transaction, |_| {
+            panic!("no events are received")
+        })
+        .await;
+
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("failed to connect to the event stream"));
+}
@@ -0,0 +1,128 @@
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::{AuthKeySource, MatchmakerConfig},
+};
+use std::collections::HashMap;
+
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+#[test]
+fn parses_toml_config() {
+    let config = MatchmakerConfig::from_toml(
+        r#"
+        network = "sepolia"
+        auth_key = { env = "FLASHBOTS_AUTH_KEY" }
+        request_timeout_ms = 2000
+        builders = ["flashbots", "rsync"]
+
+        [retry]
+        max_attempts = 3
+
+        [default_hints]
+        logs = true
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.auth_key,
+        Some(AuthKeySource::Env("FLASHBOTS_AUTH_KEY".to_string()))
+    );
+    assert_eq!(config.retry.max_attempts, 3);
+    assert_eq!(config.retry.backoff_ms, 100);
+    assert_eq!(config.network().unwrap().chain_id, 11155111);
+
+    let privacy = config.default_privacy().unwrap();
+    assert_eq!(privacy.builders, vec!["flashbots", "rsync"]);
+    assert_eq!(privacy.hints.unwrap().logs, Some(true));
+}
+
+#[test]
+fn parses_json_config_for_custom_network() {
+    let config = MatchmakerConfig::from_json(
+        r#"{
+            "network": "local",
+            "chain_id": 31337,
+            "stream_url": "http://127.0.0.1:8080",
+            "api_url": "http://127.0.0.1:8545"
+        }"#,
+    )
+    .unwrap();
+
+    let network = config.network().unwrap();
+    assert_eq!(network.name, "local");
+    assert_eq!(network.api_url, "http://127.0.0.1:8545");
+}
+
+#[test]
+fn custom_network_requires_urls() {
+    let config = MatchmakerConfig {
+        network: "local".to_string(),
+        chain_id: Some(31337),
+        ..Default::default()
+    };
+    assert!(config.network().is_err());
+}
+
+#[test]
+fn applies_overrides() {
+    let vars = HashMap::from([
+        ("API_URL", "http://127.0.0.1:8545"),
+        ("AUTH_KEY", KEY),
+        ("RETRY_MAX_ATTEMPTS", "5"),
+        ("RETRY_DEADLINE_MS", "6000"),
+        ("BUILDERS", "flashbots, titan"),
+    ]);
+    let config = MatchmakerConfig::default()
+        .with_overrides(|name| vars.get(name).map(|value| value.to_string()))
+        .unwrap();
+
+    assert_eq!(config.auth_key, Some(AuthKeySource::Key(KEY.to_string())));
+    assert_eq!(config.retry.max_attempts, 5);
+    assert_eq!(config.retry.deadline_ms, 6000);
+    assert_eq!(config.builders, vec!["flashbots", "titan"]);
+
+    let network = config.network().unwrap();
+    assert_eq!(network.name, "mainnet");
+    assert_eq!(network.api_url, "http://127.0.0.1:8545");
+}
+
+#[test]
+fn ignores_empty_overrides() {
+    let config = MatchmakerConfig {
+        auth_key: Some(AuthKeySource::Env("FLASHBOTS_AUTH_KEY".to_string())),
+        ..Default::default()
+    }
+    .with_overrides(|name| match name {
+        "AUTH_KEY" | "CHAIN_ID" => Some(String::new()),
+        "NETWORK" => Some(" ".to_string()),
+        _ => None,
+    })
+    .unwrap();
+
+    assert_eq!(
+        config.auth_key,
+        Some(AuthKeySource::Env("FLASHBOTS_AUTH_KEY".to_string()))
+    );
+    assert_eq!(config.chain_id, None);
+    assert_eq!(config.network, "mainnet");
+}
+
+#[test]
+fn rejects_invalid_override() {
+    let result = MatchmakerConfig::default()
+        .with_overrides(|name| (name == "REQUEST_TIMEOUT_MS").then(|| "soon".to_string()));
+    assert!(result.is_err());
+}
+
+#[test]
+fn builds_client_from_config() {
+    let config = MatchmakerConfig {
+        auth_key: Some(AuthKeySource::Key(KEY.to_string())),
+        request_timeout_ms: Some(500),
+        ..Default::default()
+    };
+
+    let client = MatchmakerClient::from_config(&config).unwrap();
+    assert_eq!(client.network().name, "mainnet");
+}
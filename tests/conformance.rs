@@ -0,0 +1,115 @@
+#![cfg(feature = "cli")]
+
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use std::process::{Command, Output};
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+fn conformance(target: &str) -> Output {
+    conformance_with(target, &[])
+}
+
+fn conformance_with(target: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mev-share"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["conformance", "--fixtures", "tests/tests.json", "--target"])
+        .arg(target)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn report_line<'a>(stdout: &'a str, name: &str) -> &'a str {
+    stdout
+        .lines()
+        .find(|line| line.starts_with(&format!("{name}  ")))
+        .unwrap_or_else(|| panic!("no result for {name} in\n{stdout}"))
+}
+
+#[test]
+fn local_target_passes_spec_vectors() {
+    let output = conformance("local");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.ends_with("8 passed, 0 failed, 1 skipped\n"),
+        "{stdout}"
+    );
+    assert!(report_line(&stdout, "bundle with reverting tx").contains("SKIP"));
+    assert!(
+        report_line(&stdout, "bundle with backrun of bundle with refund config").ends_with("PASS")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn endpoint_target_skips_refunds_it_can_not_compare() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({
+        "success": true,
+        "stateBlock": "0x1",
+        "mevGasPrice": "0x0",
+        "profit": "0x0",
+        "refundableValue": "0x64",
+        "gasUsed": "0x0"
+    }))])
+    .await;
+
+    let url = server.url.clone();
+    let output = tokio::task::spawn_blocking(move || conformance(&url))
+        .await
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // the mock simulates every bundle successfully, including the ones that must fail
+    assert!(!output.status.success());
+    assert!(report_line(&stdout, "simple bundle").ends_with("PASS"));
+    assert!(report_line(&stdout, "bundle with reverting tx").contains("FAIL"));
+    assert!(report_line(&stdout, "bundle with backrun of tx").contains(
+        "SKIP  refunds are not reported by mev_simBundle, only the refundable value 100"
+    ));
+    assert!(server
+        .received()
+        .iter()
+        .all(|request| request["method"] == "mev_simBundle"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn endpoint_target_passes_vectors_it_rejects_with_an_error() {
+    let server = MockServer::spawn(vec![Reply::Error(-32000)]).await;
+
+    let url = server.url.clone();
+    let output = tokio::task::spawn_blocking(move || conformance(&url))
+        .await
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(report_line(&stdout, "bundle with reverting tx").ends_with("PASS"));
+    assert!(report_line(&stdout, "simple bundle").contains("FAIL  expected to succeed"));
+}
+
+#[test]
+fn unreachable_target_fails_every_vector() {
+    let output = conformance("http://127.0.0.1:1");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert!(report_line(&stdout, "bundle with reverting tx").contains("FAIL  request failed"));
+    assert!(
+        stdout.ends_with("0 passed, 9 failed, 0 skipped\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn refuses_to_send_to_remote_targets() {
+    let output = conformance_with("https://relay.flashbots.net", &["--send"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("--allow-remote"), "{stderr}");
+    assert!(output.stdout.is_empty());
+}
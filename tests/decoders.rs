@@ -0,0 +1,218 @@
+#![cfg(feature = "decoders")]
+
+use ethers::{
+    abi::{encode, Token},
+    types::{Address, Bytes, H256, I256, U256},
+};
+use mev_share_client::{
+    decoders::{decode_log, decode_logs, decode_raw_log, DecodedEvent, LogKind, SwapDirection},
+    types::PendingTransaction,
+};
+use mev_share_rs::sse::EventTransactionLog;
+
+const V2_SYNC: &str = "0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1";
+const V2_SWAP: &str = "0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822";
+const V3_SWAP: &str = "0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67";
+const CURVE_EXCHANGE: &str = "0x8b3e96f2b889fa771c53c981b40daf005f63f637f1869f707052d15a3dd97140";
+const BALANCER_SWAP: &str = "0x2170c741c41531aec20e7c107c24eecfdd15e69c9bb0a8dd37b1840b9e0b207b";
+const TRANSFER: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+fn topic(hex: &str) -> H256 {
+    hex.parse().unwrap()
+}
+
+fn address_topic(address: Address) -> H256 {
+    H256::from(address)
+}
+
+fn log(address: Address, topics: Vec<H256>, data: Vec<Token>) -> EventTransactionLog {
+    EventTransactionLog {
+        address,
+        topics,
+        data: Bytes::from(encode(&data)),
+    }
+}
+
+#[test]
+fn identifies_events_by_signature() {
+    for (signature, kind) in [
+        (V2_SYNC, LogKind::UniswapV2Sync),
+        (V2_SWAP, LogKind::UniswapV2Swap),
+        (V3_SWAP, LogKind::UniswapV3Swap),
+        (CURVE_EXCHANGE, LogKind::CurveTokenExchange),
+        (BALANCER_SWAP, LogKind::BalancerSwap),
+        (TRANSFER, LogKind::Erc20Transfer),
+    ] {
+        assert_eq!(LogKind::identify(&[topic(signature)]), Some(kind));
+    }
+    assert_eq!(LogKind::identify(&[H256::zero()]), None);
+    assert_eq!(LogKind::identify(&[]), None);
+}
+
+#[test]
+fn decodes_uniswap_v2_sync_and_swap() {
+    let pair = Address::repeat_byte(0x11);
+    let router = Address::repeat_byte(0x22);
+    let sync = log(
+        pair,
+        vec![topic(V2_SYNC)],
+        vec![Token::Uint(1000.into()), Token::Uint(2000.into())],
+    );
+    let swap = log(
+        pair,
+        vec![topic(V2_SWAP), address_topic(router), address_topic(router)],
+        vec![
+            Token::Uint(0.into()),
+            Token::Uint(500.into()),
+            Token::Uint(240.into()),
+            Token::Uint(0.into()),
+        ],
+    );
+
+    let decoded = decode_logs(&[sync, swap]);
+
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].pool(), Some(pair));
+    let DecodedEvent::UniswapV2Sync(sync) = &decoded[0].event else {
+        panic!("expected a sync");
+    };
+    assert_eq!((sync.reserve0, sync.reserve1), (1000, 2000));
+    let DecodedEvent::UniswapV2Swap(swap) = &decoded[1].event else {
+        panic!("expected a swap");
+    };
+    assert_eq!(swap.sender, router);
+    assert_eq!(swap.amount0_out, U256::from(240));
+    assert_eq!(swap.direction(), Some(SwapDirection::OneForZero));
+}
+
+#[test]
+fn decodes_uniswap_v3_swap() {
+    let pool = Address::repeat_byte(0x33);
+    let swap = log(
+        pool,
+        vec![
+            topic(V3_SWAP),
+            address_topic(Address::repeat_byte(1)),
+            address_topic(Address::repeat_byte(2)),
+        ],
+        vec![
+            Token::Int(I256::from(100).into_raw()),
+            Token::Int(I256::from(-95).into_raw()),
+            Token::Uint(U256::one() << 96),
+            Token::Uint(10_000.into()),
+            Token::Int(I256::from(-887).into_raw()),
+        ],
+    );
+
+    let decoded = decode_log(&swap).unwrap();
+
+    let DecodedEvent::UniswapV3Swap(swap) = decoded.event else {
+        panic!("expected a v3 swap");
+    };
+    assert_eq!(swap.amount1, I256::from(-95));
+    assert_eq!(swap.tick, -887);
+    assert_eq!(swap.liquidity, 10_000);
+    assert_eq!(swap.direction(), SwapDirection::ZeroForOne);
+}
+
+#[test]
+fn decodes_curve_exchange_and_balancer_swap() {
+    let curve = log(
+        Address::repeat_byte(0x44),
+        vec![
+            topic(CURVE_EXCHANGE),
+            address_topic(Address::repeat_byte(1)),
+        ],
+        vec![
+            Token::Int(2.into()),
+            Token::Uint(1000.into()),
+            Token::Int(0.into()),
+            Token::Uint(999.into()),
+        ],
+    );
+    let DecodedEvent::CurveTokenExchange(exchange) = decode_log(&curve).unwrap().event else {
+        panic!("expected a token exchange");
+    };
+    assert_eq!((exchange.sold_id, exchange.bought_id), (2, 0));
+
+    let pool = Address::repeat_byte(0x55);
+    let mut pool_id = [0u8; 32];
+    pool_id[..20].copy_from_slice(pool.as_bytes());
+    let vault = Address::repeat_byte(0x66);
+    let balancer = log(
+        vault,
+        vec![
+            topic(BALANCER_SWAP),
+            H256(pool_id),
+            address_topic(Address::repeat_byte(7)),
+            address_topic(Address::repeat_byte(8)),
+        ],
+        vec![Token::Uint(10.into()), Token::Uint(20.into())],
+    );
+    let decoded = decode_log(&balancer).unwrap();
+    assert_eq!(decoded.address, vault);
+    assert_eq!(decoded.pool(), Some(pool));
+    let DecodedEvent::BalancerSwap(swap) = decoded.event else {
+        panic!("expected a balancer swap");
+    };
+    assert_eq!(swap.token_out, Address::repeat_byte(8));
+}
+
+#[test]
+fn decodes_erc20_transfer() {
+    let token = Address::repeat_byte(0x77);
+    let transfer = log(
+        token,
+        vec![
+            topic(TRANSFER),
+            address_topic(Address::repeat_byte(1)),
+            address_topic(Address::repeat_byte(2)),
+        ],
+        vec![Token::Uint(42.into())],
+    );
+
+    let decoded = decode_log(&transfer).unwrap();
+
+    assert_eq!(decoded.pool(), None);
+    assert_eq!(
+        decoded.event,
+        DecodedEvent::Erc20Transfer(mev_share_client::decoders::Erc20Transfer {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: 42.into(),
+        })
+    );
+}
+
+#[test]
+fn decodes_hint_logs() {
+    let pair = Address::repeat_byte(0x11);
+    let sync = log(
+        pair,
+        vec![topic(V2_SYNC)],
+        vec![Token::Uint(1.into()), Token::Uint(2.into())],
+    );
+    let unknown = log(pair, vec![H256::zero()], vec![]);
+    let hint = PendingTransaction::new(
+        H256::zero(),
+        Some(vec![sync, unknown]),
+        Some(pair),
+        None,
+        None,
+        None,
+        None,
+    );
+
+    let decoded = hint.decoded_logs();
+
+    assert_eq!(decoded.len(), 1);
+    assert!(matches!(decoded[0].event, DecodedEvent::UniswapV2Sync(_)));
+}
+
+#[test]
+fn skips_logs_without_data() {
+    let pair = Address::repeat_byte(0x11);
+
+    assert_eq!(decode_raw_log(pair, &[topic(V2_SYNC)], &Bytes::new()), None);
+    assert_eq!(decode_raw_log(pair, &[topic(V2_SWAP)], &Bytes::new()), None);
+}
@@ -0,0 +1,100 @@
+use ethers::signers::LocalWallet;
+use fixture::{wallet, BUNDLE_HASH};
+use mev_share_client::fanout::{Endpoint, FanoutError, FanoutSubmitter};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use spec::spec_bundle;
+use std::time::Duration;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+fn submitter(endpoints: Vec<Endpoint>) -> FanoutSubmitter<LocalWallet> {
+    FanoutSubmitter::new(wallet(), endpoints)
+}
+
+#[tokio::test]
+async fn sends_identical_signed_bundle_to_all_endpoints() {
+    let flashbots =
+        MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let builder =
+        MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+
+    let response = submitter(vec![
+        Endpoint::new("flashbots", &flashbots.url),
+        Endpoint::new("builder", &builder.url),
+    ])
+    .send_bundle(&spec_bundle("simple bundle"))
+    .await
+    .unwrap();
+
+    assert_eq!(response.successes().count(), 2);
+    assert_eq!(response.bundle_hash(), Some(BUNDLE_HASH.parse().unwrap()));
+    assert_eq!(flashbots.received(), builder.received());
+    assert_eq!(flashbots.received()[0]["method"], "mev_sendBundle");
+    assert_eq!(
+        flashbots.received_signatures(),
+        builder.received_signatures()
+    );
+    assert_eq!(flashbots.received_signatures().len(), 1);
+}
+
+#[tokio::test]
+async fn aggregates_per_endpoint_errors() {
+    let flashbots =
+        MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let rejecting = MockServer::spawn(vec![Reply::Error(-32602)]).await;
+    let unavailable = MockServer::spawn(vec![Reply::Status(503)]).await;
+
+    let response = submitter(vec![
+        Endpoint::new("flashbots", &flashbots.url),
+        Endpoint::new("rejecting", &rejecting.url),
+        Endpoint::new("unavailable", &unavailable.url),
+        Endpoint::new("unreachable", "http://127.0.0.1:1"),
+    ])
+    .with_timeout(Duration::from_secs(5))
+    .send_bundle(&spec_bundle("simple bundle"))
+    .await
+    .unwrap();
+
+    assert!(response.is_any_success());
+    let names: Vec<_> = response
+        .responses
+        .iter()
+        .map(|response| response.endpoint.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        ["flashbots", "rejecting", "unavailable", "unreachable"]
+    );
+
+    let errors: Vec<_> = response.errors().map(|(_, err)| err.clone()).collect();
+    assert!(matches!(errors[0], FanoutError::Rpc { code: -32602, .. }));
+    assert_eq!(errors[1], FanoutError::Status(503));
+    assert!(matches!(errors[2], FanoutError::Transport(_)));
+}
+
+#[tokio::test]
+async fn rejects_invalid_bundles_before_sending() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let mut bundle = spec_bundle("simple bundle");
+    bundle.body.clear();
+
+    assert!(submitter(vec![Endpoint::new("flashbots", &server.url)])
+        .send_bundle(&bundle)
+        .await
+        .is_err());
+    assert!(server.received().is_empty());
+}
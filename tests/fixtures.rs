@@ -0,0 +1,60 @@
+use ethers::types::{Address, U256, U64};
+use mev_share_client::{fixtures::SpecFixture, types::BundleTx};
+
+#[test]
+fn loads_bundled_spec_tests() {
+    let fixture = SpecFixture::spec();
+
+    assert_eq!(fixture.tests.len(), 9);
+    assert_eq!(fixture.header.number, U64::one());
+    assert_eq!(
+        fixture.header.miner,
+        "0x3845794654dfec95145512fd33866dbc481893ea"
+            .parse::<Address>()
+            .unwrap()
+    );
+    let contract = &fixture.genesis_alloc[&"0xc100000000000000000000000000000000000000"
+        .parse::<Address>()
+        .unwrap()];
+    assert!(contract.code.is_some());
+}
+
+#[test]
+fn parses_expected_outcomes() {
+    let fixture = SpecFixture::spec();
+
+    let simple = fixture.test("simple bundle").unwrap();
+    assert!(!simple.should_fail);
+    assert!(simple.extracted_refunds.is_empty());
+    assert!(
+        fixture
+            .test("bundle with reverting tx")
+            .unwrap()
+            .should_fail
+    );
+
+    let backrun = fixture
+        .test("bundle with backrun of bundle with refund config")
+        .unwrap();
+    assert!(matches!(backrun.bundle.body[0], BundleTx::Bundle { .. }));
+    let refund = &backrun.extracted_refunds[0];
+    assert_eq!(refund.value, U256::from(0x1636110e9abbc00u64));
+    assert_eq!(refund.percent, 90);
+    assert_eq!(refund.refund_split.values().sum::<u32>(), 100);
+}
+
+#[test]
+fn genesis_nonce_defaults_to_zero() {
+    let fixture = SpecFixture::spec();
+
+    assert_eq!(fixture.genesis_nonce(&Address::zero()), U64::zero());
+}
+
+#[test]
+fn loads_fixture_from_file() {
+    let fixture =
+        SpecFixture::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tests.json")).unwrap();
+    assert_eq!(fixture.tests.len(), SpecFixture::spec().tests.len());
+
+    assert!(SpecFixture::from_json("{}").is_err());
+}
@@ -0,0 +1,144 @@
+use ethers::types::{BlockNumber, Bytes, U256};
+use fixture::{client, BUNDLE_HASH};
+use mev_share_client::types::{CallBundleParams, CallBundleResponse, FlashbotsBundle};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const TX_HASH: &str = "0xa839ee83465657cac01adc1d50d96c1b586ed498120a84a64749c0034b4f19fa";
+
+fn call_bundle_response() -> serde_json::Value {
+    json!({
+        "bundleGasPrice": "476190476193",
+        "bundleHash": BUNDLE_HASH,
+        "coinbaseDiff": "20000000000126000",
+        "ethSentToCoinbase": "20000000000000000",
+        "gasFees": "126000",
+        "results": [
+            {
+                "coinbaseDiff": "10000000000063000",
+                "ethSentToCoinbase": "10000000000000000",
+                "fromAddress": "0x02A727155aeF8609c9f7F2179b2a1f560B39F5A0",
+                "gasFees": "63000",
+                "gasPrice": "476190476193",
+                "gasUsed": 21000,
+                "toAddress": "0x73625f59CAdc5009Cb458B751b3E7b6b48C06f2C",
+                "txHash": TX_HASH,
+                "value": "0x"
+            },
+            {
+                "coinbaseDiff": "10000000000063000",
+                "ethSentToCoinbase": "10000000000000000",
+                "fromAddress": "0x02A727155aeF8609c9f7F2179b2a1f560B39F5A0",
+                "gasFees": "63000",
+                "gasPrice": "476190476193",
+                "gasUsed": 21000,
+                "toAddress": "0x73625f59CAdc5009Cb458B751b3E7b6b48C06f2C",
+                "txHash": TX_HASH,
+                "value": "0x",
+                "revert": "execution reverted"
+            }
+        ],
+        "stateBlockNumber": 5221585,
+        "totalGasUsed": 42000
+    })
+}
+
+#[test]
+fn serializes_only_set_bundle_params() {
+    let bundle = FlashbotsBundle {
+        txs: vec![Bytes::from(vec![0x02, 0x01])],
+        block_number: 100.into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&bundle).unwrap(),
+        json!({ "txs": ["0x0201"], "blockNumber": "0x64" })
+    );
+
+    let bundle = FlashbotsBundle {
+        min_timestamp: Some(1),
+        max_timestamp: Some(2),
+        reverting_tx_hashes: vec![TX_HASH.parse().unwrap()],
+        replacement_uuid: Some("2e5b8c6f-5b1b-4b33-a3a4-4ec2a4e3e2a8".to_string()),
+        builders: vec!["flashbots".to_string()],
+        ..bundle
+    };
+    let value = serde_json::to_value(&bundle).unwrap();
+    assert_eq!(value["minTimestamp"], 1);
+    assert_eq!(value["maxTimestamp"], 2);
+    assert_eq!(value["revertingTxHashes"], json!([TX_HASH]));
+    assert_eq!(
+        value["replacementUuid"],
+        "2e5b8c6f-5b1b-4b33-a3a4-4ec2a4e3e2a8"
+    );
+    assert_eq!(value["builders"], json!(["flashbots"]));
+}
+
+#[test]
+fn decodes_call_bundle_response() {
+    let response: CallBundleResponse = serde_json::from_value(call_bundle_response()).unwrap();
+
+    assert_eq!(response.total_gas_used, 42000);
+    assert_eq!(response.state_block_number, 5221585);
+    assert_eq!(
+        response.coinbase_diff,
+        U256::from_dec_str("20000000000126000").unwrap()
+    );
+    assert!(response.results[0].is_success());
+    assert!(!response.results[1].is_success());
+    assert!(!response.is_success());
+}
+
+#[tokio::test]
+async fn sends_flashbots_bundle() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let bundle = FlashbotsBundle {
+        txs: vec![Bytes::from(vec![0x02, 0x01])],
+        block_number: 100.into(),
+        ..Default::default()
+    };
+
+    let response = client(&server)
+        .send_flashbots_bundle(&bundle)
+        .await
+        .unwrap();
+
+    assert_eq!(response.bundle_hash, BUNDLE_HASH.parse().unwrap());
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "eth_sendBundle");
+    assert_eq!(
+        request["params"],
+        json!([{ "txs": ["0x0201"], "blockNumber": "0x64" }])
+    );
+}
+
+#[tokio::test]
+async fn calls_bundle() {
+    let server = MockServer::spawn(vec![Reply::Result(call_bundle_response())]).await;
+    let params = CallBundleParams {
+        txs: vec![Bytes::from(vec![0x02, 0x01])],
+        block_number: 100.into(),
+        state_block_number: BlockNumber::Latest,
+        timestamp: None,
+    };
+
+    let response = client(&server).call_bundle(&params).await.unwrap();
+
+    assert_eq!(response.results.len(), 2);
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "eth_callBundle");
+    assert_eq!(
+        request["params"],
+        json!([{ "txs": ["0x0201"], "blockNumber": "0x64", "stateBlockNumber": "latest" }])
+    );
+}
@@ -0,0 +1,88 @@
+use fixture::client;
+use mev_share_client::types::EventHistoryParams;
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const HASH: &str = "0x2dc8b0bd8ab5a2c0b5b0b1e1e0eab8c1ee4ab2f06c5a1ab0d4d1c6a4f4d1e0b1";
+
+#[tokio::test]
+async fn gets_event_history_with_filters() {
+    let server = MockServer::spawn(vec![Reply::Json(json!([{
+        "block": 9134283,
+        "timestamp": 1686847200,
+        "hint": {
+            "hash": HASH,
+            "txs": [{
+                "to": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                "functionSelector": "0x7ff36ab5"
+            }],
+            "logs": null,
+            "gasUsed": "0x5208",
+            "mevGasPrice": "0x3b9aca00"
+        }
+    }]))])
+    .await;
+
+    let history = client(&server)
+        .get_event_history(&EventHistoryParams {
+            block_start: Some(9134283),
+            limit: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].block, 9134283);
+    assert_eq!(history[0].hint.hash, HASH.parse().unwrap());
+    let txs = history[0].hint.txs.as_ref().unwrap();
+    assert_eq!(
+        txs[0].function_selector.as_ref().unwrap().to_vec(),
+        [0x7f, 0xf3, 0x6a, 0xb5]
+    );
+    assert_eq!(
+        server.received_request_lines(),
+        ["GET /api/v1/history?blockStart=9134283&limit=1 HTTP/1.1"]
+    );
+}
+
+#[tokio::test]
+async fn gets_event_history_info() {
+    let server = MockServer::spawn(vec![Reply::Json(json!({
+        "count": 100,
+        "minBlock": 9000000,
+        "maxBlock": 9134283,
+        "minTimestamp": 1686000000,
+        "maxTimestamp": 1686847200,
+        "maxLimit": 500
+    }))])
+    .await;
+
+    let info = client(&server).get_event_history_info().await.unwrap();
+
+    assert_eq!(info.count, 100);
+    assert_eq!(info.max_limit, 500);
+    assert_eq!(
+        server.received_request_lines(),
+        ["GET /api/v1/history/info HTTP/1.1"]
+    );
+}
+
+#[tokio::test]
+async fn fails_on_error_status() {
+    let server = MockServer::spawn(vec![Reply::Status(503)]).await;
+
+    let err = client(&server).get_event_history_info().await.unwrap_err();
+
+    assert!(err.to_string().contains("503"));
+}
@@ -0,0 +1,173 @@
+use ethers::{
+    providers::Provider,
+    types::{Block, TxHash, U64},
+};
+use futures_util::{
+    future::{BoxFuture, FutureExt},
+    stream::{self, BoxStream, StreamExt},
+};
+use mev_share_client::{
+    blocks::{BlockContents, BlockSource, PollingBlockSource},
+    inclusion::{InclusionStatus, InclusionTracker},
+    types::{Bundle, BundleTx, InclusionParams},
+};
+use std::{collections::HashMap, time::Duration};
+
+/// Chain stand-in with fixed blocks, all produced at once
+struct Chain(HashMap<u64, Vec<TxHash>>);
+
+impl Chain {
+    fn new(blocks: &[(u64, &[TxHash])]) -> Self {
+        Chain(
+            blocks
+                .iter()
+                .map(|(number, txs)| (*number, txs.to_vec()))
+                .collect(),
+        )
+    }
+}
+
+impl BlockSource for Chain {
+    fn block_numbers(&self) -> BoxStream<'_, U64> {
+        let mut numbers: Vec<_> = self.0.keys().copied().collect();
+        numbers.sort();
+        stream::iter(numbers.into_iter().map(U64::from)).boxed()
+    }
+}
+
+impl BlockContents for Chain {
+    fn block_transactions(
+        &self,
+        number: U64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<Vec<TxHash>>>> {
+        let transactions = self.0.get(&number.as_u64()).cloned();
+        async move { Ok(transactions) }.boxed()
+    }
+}
+
+fn hash(byte: u8) -> TxHash {
+    TxHash::repeat_byte(byte)
+}
+
+fn backrun_bundle(block: u64, max_block: u64) -> Bundle {
+    Bundle {
+        inclusion: InclusionParams {
+            block: block.into(),
+            max_block: Some(max_block.into()),
+        },
+        body: vec![
+            BundleTx::TxHash { hash: hash(1) },
+            BundleTx::TxHash { hash: hash(2) },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn collects_nested_bundle_hashes_in_order() {
+    let mut bundle = backrun_bundle(10, 10);
+    bundle.body.insert(
+        0,
+        BundleTx::Bundle {
+            bundle: Box::new(Bundle {
+                body: vec![BundleTx::TxHash { hash: hash(9) }],
+                ..Default::default()
+            }),
+        },
+    );
+
+    assert_eq!(
+        InclusionTracker::for_bundle(&bundle).tx_hashes(),
+        [hash(9), hash(1), hash(2)]
+    );
+}
+
+#[tokio::test]
+async fn finds_bundle_position() {
+    let chain = Chain::new(&[
+        (10, &[hash(7)]),
+        (11, &[hash(7), hash(8), hash(1), hash(2), hash(3)]),
+    ]);
+
+    let status = InclusionTracker::for_bundle(&backrun_bundle(10, 12))
+        .track(&chain)
+        .await;
+
+    assert_eq!(
+        status,
+        Some(InclusionStatus::Included {
+            block: 11.into(),
+            position: 2
+        })
+    );
+}
+
+#[tokio::test]
+async fn reports_outbid_when_target_lands_without_backrun() {
+    let chain = Chain::new(&[(10, &[hash(1), hash(5)])]);
+
+    let status = InclusionTracker::for_bundle(&backrun_bundle(10, 12))
+        .track(&chain)
+        .await;
+
+    assert_eq!(status, Some(InclusionStatus::Outbid { block: 10.into() }));
+}
+
+#[tokio::test]
+async fn reports_outbid_when_out_of_order() {
+    let chain = Chain::new(&[(10, &[hash(2), hash(1)])]);
+
+    let status = InclusionTracker::for_bundle(&backrun_bundle(10, 10))
+        .track(&chain)
+        .await;
+
+    assert_eq!(status, Some(InclusionStatus::Outbid { block: 10.into() }));
+}
+
+#[tokio::test]
+async fn expires_after_max_block() {
+    let chain = Chain::new(&[(9, &[hash(1), hash(2)]), (10, &[]), (11, &[]), (12, &[])]);
+
+    let status = InclusionTracker::for_bundle(&backrun_bundle(10, 11))
+        .track(&chain)
+        .await;
+
+    assert_eq!(status, Some(InclusionStatus::Expired));
+}
+
+#[tokio::test]
+async fn unresolved_when_source_ends() {
+    let chain = Chain::new(&[(10, &[])]);
+
+    let status = InclusionTracker::for_bundle(&backrun_bundle(10, 11))
+        .track(&chain)
+        .await;
+
+    assert_eq!(status, None);
+}
+
+#[tokio::test]
+async fn tracks_with_provider() {
+    let (provider, mock) = Provider::mocked();
+    // responses are returned last in, first out: block number, then block 10
+    mock.push(Block::<TxHash> {
+        number: Some(10.into()),
+        transactions: vec![hash(1), hash(2)],
+        ..Default::default()
+    })
+    .unwrap();
+    mock.push(U64::from(10)).unwrap();
+
+    let source = PollingBlockSource::new(provider, Duration::from_millis(10));
+    let status = InclusionTracker::for_bundle(&backrun_bundle(10, 10))
+        .track(&source)
+        .await;
+
+    assert_eq!(
+        status,
+        Some(InclusionStatus::Included {
+            block: 10.into(),
+            position: 0
+        })
+    );
+}
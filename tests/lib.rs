@@ -1,4 +1,58 @@
 #![allow(dead_code)]
 
+#[cfg(test)]
+mod bundle;
+#[cfg(test)]
+mod cancellation;
+#[cfg(test)]
+mod cli;
+#[cfg(test)]
+mod client;
+#[cfg(test)]
+mod config;
+#[cfg(test)]
+mod conformance;
+#[cfg(test)]
+mod decoders;
+#[cfg(test)]
+mod fanout;
+#[cfg(test)]
+mod fixtures;
+#[cfg(test)]
+mod flashbots_bundle;
+#[cfg(test)]
+mod history;
+#[cfg(test)]
+mod inclusion;
+#[cfg(test)]
+mod metrics;
+#[cfg(test)]
+mod networks;
+#[cfg(test)]
+mod nonce_manager;
+#[cfg(test)]
+mod private_transaction;
+#[cfg(test)]
+mod rate_limit;
+#[cfg(test)]
+mod retry;
+#[cfg(test)]
+mod scheduler;
+#[cfg(test)]
+mod selectors;
+#[cfg(test)]
+mod simulation;
+#[cfg(test)]
+mod stats;
+#[cfg(test)]
+mod strategy;
+#[cfg(test)]
+mod telemetry;
 #[cfg(test)]
 mod test;
+#[cfg(test)]
+mod transaction;
+#[cfg(test)]
+mod uniswap_v2;
+#[cfg(test)]
+mod validation;
@@ -0,0 +1,45 @@
+#![cfg(feature = "metrics")]
+
+use ethers::signers::LocalWallet;
+use mev_share_client::{client::MatchmakerClient, metrics::render, types::MatchMakerNetwork};
+use spec::spec_bundle;
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+fn unreachable_client() -> MatchmakerClient<LocalWallet> {
+    let network = MatchMakerNetwork {
+        chain_id: 31337,
+        name: "unreachable".to_string(),
+        stream_url: "http://127.0.0.1:1".to_string(),
+        api_url: "http://127.0.0.1:1".to_string(),
+    };
+    let wallet: LocalWallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        .parse()
+        .unwrap();
+    MatchmakerClient::use_network(wallet, network)
+}
+
+#[tokio::test]
+async fn records_failed_requests() {
+    let client = unreachable_client();
+    assert!(client
+        .send_bundle(&spec_bundle("simple bundle"))
+        .await
+        .is_err());
+
+    let rendered = render();
+    assert!(rendered
+        .contains(r#"mev_share_requests_total{method="mev_sendBundle",status="transport"}"#));
+    assert!(rendered.contains("mev_share_request_duration_seconds_count"));
+    assert!(rendered.contains("mev_share_signing_duration_seconds_count"));
+}
+
+#[test]
+fn registers_stream_metrics() {
+    let rendered = render();
+    assert!(rendered.contains("mev_share_event_decode_errors_total"));
+    assert!(rendered.contains("mev_share_stream_reconnects_total"));
+}
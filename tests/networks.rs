@@ -0,0 +1,89 @@
+use ethers::types::Chain;
+use mev_share_client::types::{MatchMakerNetwork, SupportedNetworks, HOLESKY_CHAIN_ID};
+
+fn local_network() -> MatchMakerNetwork {
+    MatchMakerNetwork {
+        chain_id: 31337,
+        name: "local".to_string(),
+        stream_url: "http://127.0.0.1:8080".to_string(),
+        api_url: "http://127.0.0.1:8545".to_string(),
+    }
+}
+
+#[test]
+fn includes_testnets() {
+    let networks = SupportedNetworks::new();
+
+    let sepolia = networks.sepolia().unwrap();
+    assert_eq!(sepolia.chain_id, Chain::Sepolia as u64);
+    assert_eq!(sepolia.api_url, "https://relay-sepolia.flashbots.net");
+
+    let holesky = networks.get_by_chain_id(HOLESKY_CHAIN_ID).unwrap();
+    assert_eq!(holesky.name, "holesky");
+}
+
+#[test]
+fn registers_custom_network() {
+    let mut networks = SupportedNetworks::new();
+    assert!(!networks.is_supported(31337));
+
+    assert!(networks.register(local_network()).is_none());
+    assert!(networks.is_supported(31337));
+    assert_eq!(
+        networks.get_by_name("local").unwrap().api_url,
+        "http://127.0.0.1:8545"
+    );
+    assert_eq!(networks.get_network(31337).unwrap().name, "local");
+}
+
+#[test]
+fn register_replaces_network_with_same_name() {
+    let mut networks = SupportedNetworks::new();
+    networks.register(local_network());
+
+    let replaced = networks.register(MatchMakerNetwork {
+        api_url: "http://127.0.0.1:9545".to_string(),
+        ..local_network()
+    });
+    assert_eq!(replaced.unwrap().api_url, "http://127.0.0.1:8545");
+    assert_eq!(
+        networks.get_by_name("local").unwrap().api_url,
+        "http://127.0.0.1:9545"
+    );
+}
+
+#[test]
+fn prefers_predefined_network_for_shared_chain_id() {
+    let mut networks = SupportedNetworks::new();
+    for name in ["mainnet-fork", "mainnet-staging", "mainnet-local"] {
+        networks.register(MatchMakerNetwork {
+            chain_id: Chain::Mainnet as u64,
+            name: name.to_string(),
+            ..local_network()
+        });
+    }
+
+    assert_eq!(networks.get_by_chain_id(1).unwrap().name, "mainnet");
+    assert_eq!(networks.get_network(1).unwrap().name, "mainnet");
+    assert_eq!(
+        networks.get_by_name("mainnet-staging").unwrap().stream_url,
+        "http://127.0.0.1:8080"
+    );
+}
+
+#[test]
+fn replaced_network_keeps_its_precedence() {
+    let mut networks = SupportedNetworks::new();
+    networks.register(MatchMakerNetwork {
+        chain_id: Chain::Sepolia as u64,
+        ..local_network()
+    });
+    networks.register(MatchMakerNetwork {
+        api_url: "http://127.0.0.1:9545".to_string(),
+        ..networks.sepolia().unwrap().clone()
+    });
+
+    let sepolia = networks.get_by_chain_id(Chain::Sepolia as u64).unwrap();
+    assert_eq!(sepolia.name, "sepolia");
+    assert_eq!(sepolia.api_url, "http://127.0.0.1:9545");
+}
@@ -0,0 +1,126 @@
+use ethers::{
+    providers::Provider,
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2718::TypedTransaction, Address, TransactionRequest, U256, U64},
+};
+use mev_share_client::{
+    nonce_manager::NonceManager,
+    types::{Bundle, InclusionParams, MatchMakerNetwork},
+};
+use std::sync::Arc;
+
+#[test]
+fn reserves_sequential_nonces() {
+    let nonces = NonceManager::new();
+    let address = Address::random();
+    nonces.set_next(address, 3.into());
+
+    assert_eq!(nonces.reserve(address, 10.into()).nonce, 3.into());
+    assert_eq!(nonces.reserve(address, 10.into()).nonce, 4.into());
+    assert_eq!(nonces.peek(address), 5.into());
+}
+
+#[test]
+fn reuses_released_and_expired_nonces() {
+    let nonces = NonceManager::new();
+    let address = Address::random();
+
+    let first = nonces.reserve(address, 10.into());
+    let second = nonces.reserve(address, 12.into());
+    nonces.release(&first);
+    assert_eq!(nonces.peek(address), first.nonce);
+
+    nonces.reserve(address, 10.into());
+    nonces.on_block(13.into());
+    assert_eq!(nonces.peek(address), U256::zero());
+    assert_eq!(second.nonce, U256::one());
+}
+
+#[test]
+fn keeps_expired_nonces_below_reserved_ones() {
+    let nonces = NonceManager::new();
+    let address = Address::random();
+
+    let first = nonces.reserve(address, 10.into());
+    let second = nonces.reserve(address, 12.into());
+    let third = nonces.reserve(address, 10.into());
+
+    nonces.on_block(11.into());
+    assert_eq!(nonces.peek(address), third.nonce);
+
+    nonces.on_block(13.into());
+    assert_eq!(nonces.peek(address), first.nonce);
+    assert_eq!(second.nonce, U256::one());
+}
+
+#[test]
+fn confirm_advances_past_included_nonce() {
+    let nonces = NonceManager::new();
+    let address = Address::random();
+
+    let first = nonces.reserve(address, 10.into());
+    nonces.reserve(address, 10.into());
+    nonces.confirm(&first);
+    nonces.on_block(11.into());
+
+    assert_eq!(nonces.peek(address), U256::one());
+}
+
+#[tokio::test]
+async fn syncs_from_provider() {
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(42)).unwrap();
+
+    let nonces = NonceManager::new();
+    let address = Address::random();
+    nonces.reserve(address, 1.into());
+
+    assert_eq!(nonces.sync(&provider, address).await.unwrap(), 42.into());
+    assert_eq!(nonces.peek(address), 42.into());
+}
+
+#[tokio::test]
+async fn concurrent_bundles_get_distinct_nonces() {
+    let nonces = Arc::new(NonceManager::new());
+    let wallet: LocalWallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        .parse()
+        .unwrap();
+    let network = MatchMakerNetwork {
+        chain_id: 1,
+        ..Default::default()
+    };
+
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let nonces = nonces.clone();
+            let wallet = wallet.clone();
+            let network = network.clone();
+            tokio::spawn(async move {
+                let mut bundle = Bundle {
+                    inclusion: InclusionParams {
+                        block: U64::from(1),
+                        max_block: None,
+                    },
+                    ..Default::default()
+                };
+                let tx: TypedTransaction = TransactionRequest::new()
+                    .to(wallet.address())
+                    .gas(21000)
+                    .gas_price(1)
+                    .into();
+                bundle
+                    .push_signed_with_nonce(&nonces, &wallet, &network, tx, false)
+                    .await
+                    .unwrap();
+                bundle.body[0].decode().unwrap().unwrap().nonce
+            })
+        })
+        .collect();
+
+    let mut used = Vec::new();
+    for task in tasks {
+        used.push(task.await.unwrap());
+    }
+    used.sort();
+    assert_eq!(used, (0..4).map(U256::from).collect::<Vec<_>>());
+}
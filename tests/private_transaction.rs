@@ -0,0 +1,108 @@
+use ethers::types::{Bytes, TxHash};
+use fixture::client;
+use mev_share_client::types::{HintPreference, TransactionOptions};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const TX_HASH: &str = "0x2dc8b0bd8ab5a2c0b5b0b1e1e0eab8c1ee4ab2f06c5a1ab0d4d1c6a4f4d1e0b1";
+
+#[tokio::test]
+async fn sends_private_transaction_with_preferences() {
+    let server = MockServer::spawn(vec![Reply::Result(json!(TX_HASH))]).await;
+    let options = TransactionOptions {
+        hints: Some(HintPreference {
+            calldata: Some(true),
+            logs: Some(false),
+            ..Default::default()
+        }),
+        max_block_number: Some(100.into()),
+        builders: Some(vec!["flashbots".to_string()]),
+    };
+
+    let tx_hash = client(&server)
+        .send_private_transaction(Bytes::from(vec![0x02, 0x01]), &options)
+        .await
+        .unwrap();
+
+    assert_eq!(tx_hash, TX_HASH.parse::<TxHash>().unwrap());
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "eth_sendPrivateTransaction");
+    assert_eq!(
+        request["params"][0],
+        json!({
+            "tx": "0x0201",
+            "maxBlockNumber": "0x64",
+            "preferences": {
+                "fast": true,
+                "privacy": {
+                    "hints": ["calldata"],
+                    "builders": ["flashbots"]
+                }
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn omits_privacy_without_hints_or_builders() {
+    let server = MockServer::spawn(vec![Reply::Result(json!(TX_HASH))]).await;
+
+    client(&server)
+        .send_private_transaction(Bytes::from(vec![0x02]), &TransactionOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        server.received()[0]["params"][0],
+        json!({ "tx": "0x02", "preferences": { "fast": true } })
+    );
+}
+
+#[test]
+fn hint_preference_is_a_list_of_shared_hints() {
+    let hints = HintPreference {
+        contract_address: Some(true),
+        logs: Some(true),
+        tx_hash: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&hints).unwrap(),
+        json!(["contract_address", "logs"])
+    );
+    assert_eq!(
+        serde_json::to_value(HintPreference::default()).unwrap(),
+        json!([])
+    );
+    assert!(serde_json::from_value::<HintPreference>(json!(["everything"])).is_err());
+}
+
+#[test]
+fn hint_preference_round_trips() {
+    let hints = HintPreference {
+        calldata: Some(true),
+        function_selector: Some(true),
+        hash: Some(true),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(&hints).unwrap();
+    assert_eq!(json, json!(["calldata", "function_selector", "hash"]));
+    assert_eq!(
+        serde_json::from_value::<HintPreference>(json).unwrap(),
+        hints
+    );
+
+    let flags: HintPreference =
+        serde_json::from_value(json!({ "calldata": true, "logs": false })).unwrap();
+    assert_eq!(flags.shared(), vec!["calldata"]);
+}
@@ -0,0 +1,157 @@
+use fixture::{config, BUNDLE_HASH};
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::{MatchmakerConfig, RateBudget, RateLimitConfig, RetryConfig, ThrottlePolicy},
+    rate_limit::THROTTLED_ERROR_CODE,
+};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use spec::spec_bundle;
+use std::{collections::HashMap, time::Instant};
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+fn client(
+    server: &MockServer,
+    rate_limit: RateLimitConfig,
+) -> MatchmakerClient<ethers::signers::LocalWallet> {
+    MatchmakerClient::from_config(&MatchmakerConfig {
+        rate_limit,
+        ..config(server)
+    })
+    .unwrap()
+}
+
+fn send_bundle_budget(per_second: f64, policy: ThrottlePolicy) -> RateLimitConfig {
+    RateLimitConfig {
+        methods: HashMap::from([(
+            "mev_sendBundle".to_string(),
+            RateBudget {
+                per_second,
+                burst: 1,
+            },
+        )]),
+        policy,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn parses_rate_limit_config() {
+    let config = MatchmakerConfig::from_toml(
+        r#"
+        [rate_limit]
+        policy = "drop"
+        default = { per_second = 10.0, burst = 5 }
+        methods.mev_sendBundle = { per_second = 2.0, burst = 1 }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.rate_limit.policy, ThrottlePolicy::Drop);
+    assert_eq!(config.rate_limit.default.unwrap().burst, 5);
+    assert_eq!(config.rate_limit.methods["mev_sendBundle"].per_second, 2.0);
+    assert!(MatchmakerConfig::default().rate_limit.is_unlimited());
+}
+
+#[tokio::test]
+async fn rejects_budgets_that_never_refill() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let config = |rate_limit| MatchmakerConfig {
+        rate_limit,
+        ..config(&server)
+    };
+
+    for per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let rate_limit = send_bundle_budget(per_second, ThrottlePolicy::Queue);
+        assert!(MatchmakerClient::from_config(&config(rate_limit)).is_err());
+    }
+    let empty_burst = RateLimitConfig {
+        default: Some(RateBudget {
+            per_second: 1.0,
+            burst: 0,
+        }),
+        ..Default::default()
+    };
+    assert!(MatchmakerClient::from_config(&config(empty_burst)).is_err());
+    assert!(MatchmakerConfig::from_toml(
+        "rate_limit.methods.mev_sendBundle = { per_second = 0.0, burst = 1 }"
+    )
+    .is_err());
+}
+
+#[test]
+fn throttled_requests_are_not_retried_by_default() {
+    assert!(!RetryConfig::default()
+        .retryable_codes
+        .contains(&THROTTLED_ERROR_CODE));
+}
+
+#[tokio::test]
+async fn drops_requests_over_budget() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let client = client(&server, send_bundle_budget(0.1, ThrottlePolicy::Drop));
+
+    let bundle = spec_bundle("simple bundle");
+    assert!(client.send_bundle(&bundle).await.is_ok());
+    let err = client.send_bundle(&bundle).await.unwrap_err();
+    assert!(
+        err.to_string().contains(&THROTTLED_ERROR_CODE.to_string()),
+        "{err}"
+    );
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn queues_requests_over_budget() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let client = client(&server, send_bundle_budget(20.0, ThrottlePolicy::Queue));
+
+    let start = Instant::now();
+    let bundle = spec_bundle("simple bundle");
+    for _ in 0..3 {
+        client.send_bundle(&bundle).await.unwrap();
+    }
+
+    // the burst covers the first request, the others wait 50ms each
+    assert!(start.elapsed().as_millis() >= 90);
+    assert_eq!(server.received().len(), 3);
+}
+
+#[tokio::test]
+async fn methods_without_budget_are_not_limited() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let client = client(
+        &server,
+        RateLimitConfig {
+            methods: HashMap::from([(
+                "eth_sendPrivateTransaction".to_string(),
+                RateBudget {
+                    per_second: 0.1,
+                    burst: 1,
+                },
+            )]),
+            policy: ThrottlePolicy::Drop,
+            ..Default::default()
+        },
+    );
+
+    let bundle = spec_bundle("simple bundle");
+    for _ in 0..3 {
+        client.send_bundle(&bundle).await.unwrap();
+    }
+    assert_eq!(server.received().len(), 3);
+}
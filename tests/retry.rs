@@ -0,0 +1,166 @@
+use fixture::{config, BUNDLE_HASH};
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::{MatchmakerConfig, RetryConfig},
+    retry::with_deadline,
+};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use spec::spec_bundle;
+use std::time::{Duration, Instant};
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+fn client(
+    server: &MockServer,
+    max_attempts: u32,
+) -> MatchmakerClient<ethers::signers::LocalWallet> {
+    MatchmakerClient::from_config(&MatchmakerConfig {
+        retry: RetryConfig {
+            max_attempts,
+            backoff_ms: 1,
+            ..Default::default()
+        },
+        ..config(server)
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn retries_transient_errors_with_identical_bundle() {
+    let server = MockServer::spawn(vec![
+        Reply::Error(-32603),
+        Reply::Status(503),
+        Reply::Result(json!({ "bundleHash": BUNDLE_HASH })),
+    ])
+    .await;
+
+    let response = client(&server, 3)
+        .send_bundle(&spec_bundle("simple bundle"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.bundle_hash, BUNDLE_HASH.parse().unwrap());
+    let received = server.received();
+    assert_eq!(received.len(), 3);
+    assert!(received.iter().all(|request| *request == received[0]));
+}
+
+#[tokio::test]
+async fn does_not_retry_other_errors() {
+    let server = MockServer::spawn(vec![Reply::Error(-32602)]).await;
+
+    assert!(client(&server, 3)
+        .send_bundle(&spec_bundle("simple bundle"))
+        .await
+        .is_err());
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = MockServer::spawn(vec![Reply::Error(-32603)]).await;
+
+    assert!(client(&server, 2)
+        .send_bundle(&spec_bundle("simple bundle"))
+        .await
+        .is_err());
+    assert_eq!(server.received().len(), 2);
+}
+
+#[tokio::test]
+async fn single_attempt_by_default() {
+    let server = MockServer::spawn(vec![Reply::Status(503)]).await;
+
+    assert!(client(&server, RetryConfig::default().max_attempts)
+        .send_bundle(&spec_bundle("simple bundle"))
+        .await
+        .is_err());
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn stops_retrying_at_the_request_deadline() {
+    let server = MockServer::spawn(vec![Reply::Error(-32603)]).await;
+    let client = client(&server, 1000);
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_millis(50);
+    assert!(
+        with_deadline(deadline, client.send_bundle(&spec_bundle("simple bundle")))
+            .await
+            .is_err()
+    );
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let attempts = server.received().len();
+    assert!(attempts > 1 && attempts < 1000, "{attempts} attempts");
+}
+
+#[tokio::test]
+async fn sends_one_attempt_after_the_deadline() {
+    let server = MockServer::spawn(vec![Reply::Error(-32603)]).await;
+    let client = client(&server, 3);
+
+    let passed = Instant::now() - Duration::from_millis(1);
+    assert!(
+        with_deadline(passed, client.send_bundle(&spec_bundle("simple bundle")))
+            .await
+            .is_err()
+    );
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn nested_deadlines_keep_the_earlier_one() {
+    let server = MockServer::spawn(vec![Reply::Error(-32603)]).await;
+    let client = client(&server, 3);
+
+    let passed = Instant::now() - Duration::from_millis(1);
+    let later = Instant::now() + Duration::from_secs(60);
+    let bundle = spec_bundle("simple bundle");
+    let send = with_deadline(later, client.send_bundle(&bundle));
+    assert!(with_deadline(passed, send).await.is_err());
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn retries_bundles_until_their_last_block_is_due() {
+    let server = MockServer::spawn(vec![Reply::Error(-32603)]).await;
+    let client = MatchmakerClient::from_config(&MatchmakerConfig {
+        retry: RetryConfig {
+            max_attempts: 1000,
+            backoff_ms: 1,
+            block_time_ms: 20,
+            ..Default::default()
+        },
+        ..config(&server)
+    })
+    .unwrap();
+    let mut bundle = spec_bundle("simple bundle");
+    bundle.inclusion.max_block = Some(bundle.inclusion.block + 2);
+
+    let started = Instant::now();
+    assert!(client.send_bundle(&bundle).await.is_err());
+
+    let elapsed = started.elapsed();
+    assert!(
+        elapsed >= Duration::from_millis(50) && elapsed < Duration::from_secs(1),
+        "{elapsed:?}"
+    );
+    let attempts = server.received().len();
+    assert!(attempts > 1 && attempts < 1000, "{attempts} attempts");
+}
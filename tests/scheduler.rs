@@ -0,0 +1,160 @@
+use ethers::{
+    providers::{MockProvider, Provider},
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2718::TypedTransaction, TransactionReceipt, TransactionRequest, U64},
+};
+use fixture::{client, wallet, BUNDLE_HASH};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use mev_share_client::{
+    blocks::BlockSource,
+    scheduler::{BundleScheduler, CancelHandle, ScheduleStatus},
+    types::{Bundle, InclusionParams},
+};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+struct Blocks(Vec<u64>);
+
+impl BlockSource for Blocks {
+    fn block_numbers(&self) -> BoxStream<'_, U64> {
+        stream::iter(self.0.iter().map(|block| U64::from(*block))).boxed()
+    }
+}
+
+struct NoBlocks;
+
+impl BlockSource for NoBlocks {
+    fn block_numbers(&self) -> BoxStream<'_, U64> {
+        stream::pending().boxed()
+    }
+}
+
+async fn scheduler() -> (
+    BundleScheduler<LocalWallet, Provider<MockProvider>>,
+    MockProvider,
+    MockServer,
+) {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let (provider, mock) = Provider::mocked();
+
+    (
+        BundleScheduler::new(client(&server), provider),
+        mock,
+        server,
+    )
+}
+
+async fn signed_bundle(block: u64, max_block: u64) -> Bundle {
+    let wallet = wallet();
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(wallet.address())
+        .nonce(0)
+        .gas(21000)
+        .gas_price(1)
+        .into();
+
+    let mut bundle = Bundle {
+        inclusion: InclusionParams {
+            block: block.into(),
+            max_block: Some(max_block.into()),
+        },
+        ..Default::default()
+    };
+    bundle
+        .push_signed(&wallet, &Default::default(), tx, false)
+        .await
+        .unwrap();
+    bundle
+}
+
+fn no_receipt(mock: &MockProvider) {
+    mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+}
+
+#[tokio::test]
+async fn resubmits_until_landed() {
+    let (scheduler, mock, server) = scheduler().await;
+    // responses are returned last in, first out
+    mock.push(TransactionReceipt {
+        block_number: Some(101.into()),
+        ..Default::default()
+    })
+    .unwrap();
+    no_receipt(&mock);
+
+    let report = scheduler
+        .run(
+            signed_bundle(100, 105).await,
+            &Blocks(vec![100, 101, 102]),
+            &CancelHandle::new(),
+        )
+        .await;
+
+    assert_eq!(report.status, ScheduleStatus::Landed { block: 101.into() });
+    let targets: Vec<_> = report
+        .submissions
+        .iter()
+        .map(|submission| submission.target_block.as_u64())
+        .collect();
+    assert_eq!(targets, [100, 101]);
+    let received = server.received();
+    assert_eq!(received[1]["params"][0]["inclusion"]["block"], "0x65");
+    assert_eq!(received[1]["params"][0]["inclusion"]["maxBlock"], "0x69");
+}
+
+#[tokio::test]
+async fn expires_after_max_block() {
+    let (scheduler, mock, _server) = scheduler().await;
+    no_receipt(&mock);
+    no_receipt(&mock);
+
+    let report = scheduler
+        .run(
+            signed_bundle(100, 101).await,
+            &Blocks(vec![100, 101, 102]),
+            &CancelHandle::new(),
+        )
+        .await;
+
+    assert_eq!(report.status, ScheduleStatus::Expired);
+    assert_eq!(report.submissions.len(), 2);
+    assert!(report
+        .submissions
+        .iter()
+        .all(|submission| submission.result.is_ok()));
+}
+
+#[tokio::test]
+async fn stops_when_cancelled() {
+    let (scheduler, _mock, server) = scheduler().await;
+    let cancel = CancelHandle::new();
+
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        canceller.cancel();
+    });
+    let report = scheduler
+        .run(signed_bundle(100, 105).await, &NoBlocks, &cancel)
+        .await;
+
+    assert_eq!(report.status, ScheduleStatus::Cancelled);
+    assert_eq!(report.submissions.len(), 1);
+    assert_eq!(server.received().len(), 1);
+
+    let report = scheduler
+        .run(signed_bundle(100, 105).await, &NoBlocks, &cancel)
+        .await;
+    assert_eq!(report.status, ScheduleStatus::Cancelled);
+    assert!(report.submissions.is_empty());
+}
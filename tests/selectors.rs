@@ -0,0 +1,203 @@
+use ethers::{
+    abi::{encode, Token},
+    types::{Address, Bytes, H256, U256},
+};
+use mev_share_client::{selectors::SelectorRegistry, types::PendingTransaction};
+use mev_share_rs::sse::FunctionSelector;
+
+const SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = [0x38, 0xed, 0x17, 0x39];
+const EXACT_INPUT: [u8; 4] = [0xc0, 0x4b, 0x8d, 0x59];
+const EXACT_INPUT_SINGLE_02: [u8; 4] = [0x04, 0xe4, 0x5a, 0xaf];
+
+fn calldata(selector: [u8; 4], params: &[Token]) -> Vec<u8> {
+    [selector.as_slice(), &encode(params)].concat()
+}
+
+fn hint(selector: Option<[u8; 4]>, calldata: Option<Vec<u8>>) -> PendingTransaction {
+    PendingTransaction::new(
+        H256::zero(),
+        None,
+        Some(Address::random()),
+        selector.map(FunctionSelector),
+        calldata.map(Bytes::from),
+        None,
+        None,
+    )
+}
+
+#[test]
+fn seeds_known_selectors() {
+    let registry = SelectorRegistry::new();
+
+    for (selector, signature) in [
+        (
+            SWAP_EXACT_TOKENS_FOR_TOKENS,
+            "swapExactTokensForTokens(uint256,uint256,address[],address,uint256)",
+        ),
+        (
+            [0x41, 0x4b, 0xf3, 0x89],
+            "exactInputSingle((address,address,uint24,address,uint256,uint256,uint256,uint160))",
+        ),
+        (
+            EXACT_INPUT_SINGLE_02,
+            "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160))",
+        ),
+        (
+            EXACT_INPUT,
+            "exactInput((bytes,address,uint256,uint256,uint256))",
+        ),
+        ([0x5a, 0xe4, 0x01, 0xdc], "multicall(uint256,bytes[])"),
+        ([0x35, 0x93, 0x56, 0x4c], "execute(bytes,bytes[],uint256)"),
+        ([0x09, 0x5e, 0xa7, 0xb3], "approve(address,uint256)"),
+    ] {
+        let function = registry.function(selector).expect(signature);
+        assert_eq!(function.signature(), signature);
+    }
+    assert!(registry.function([0xde, 0xad, 0xbe, 0xef]).is_none());
+}
+
+#[test]
+fn decodes_named_params_and_v2_path() {
+    let registry = SelectorRegistry::new();
+    let path = vec![Address::random(), Address::random(), Address::random()];
+    let to = Address::random();
+    let data = calldata(
+        SWAP_EXACT_TOKENS_FOR_TOKENS,
+        &[
+            Token::Uint(U256::from(1000)),
+            Token::Uint(U256::from(990)),
+            Token::Array(path.iter().copied().map(Token::Address).collect()),
+            Token::Address(to),
+            Token::Uint(U256::from(1_700_000_000)),
+        ],
+    );
+
+    let call = registry.decode(&data).unwrap();
+
+    assert_eq!(call.name, "swapExactTokensForTokens");
+    assert_eq!(call.param("amountIn"), Some(&Token::Uint(U256::from(1000))));
+    assert_eq!(call.param("to"), Some(&Token::Address(to)));
+    assert_eq!(call.param("missing"), None);
+    assert_eq!(call.path(), Some(path));
+}
+
+#[test]
+fn decodes_v3_paths() {
+    let registry = SelectorRegistry::new();
+    let (token_a, token_b, token_c) = (Address::random(), Address::random(), Address::random());
+    let fee = [0x00, 0x0b, 0xb8];
+    let packed = [
+        token_a.as_bytes(),
+        &fee,
+        token_b.as_bytes(),
+        &fee,
+        token_c.as_bytes(),
+    ]
+    .concat();
+
+    let exact_input = calldata(
+        EXACT_INPUT,
+        &[Token::Tuple(vec![
+            Token::Bytes(packed),
+            Token::Address(Address::random()),
+            Token::Uint(U256::from(1_700_000_000)),
+            Token::Uint(U256::from(1000)),
+            Token::Uint(U256::zero()),
+        ])],
+    );
+    let exact_input_single = calldata(
+        EXACT_INPUT_SINGLE_02,
+        &[Token::Tuple(vec![
+            Token::Address(token_a),
+            Token::Address(token_b),
+            Token::Uint(U256::from(3000)),
+            Token::Address(Address::random()),
+            Token::Uint(U256::from(1000)),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::zero()),
+        ])],
+    );
+
+    assert_eq!(
+        registry.decode(&exact_input).unwrap().path(),
+        Some(vec![token_a, token_b, token_c])
+    );
+    assert_eq!(
+        registry.decode(&exact_input_single).unwrap().path(),
+        Some(vec![token_a, token_b])
+    );
+}
+
+#[test]
+fn rejects_unknown_or_malformed_calldata() {
+    let registry = SelectorRegistry::new();
+
+    assert!(registry.decode(&[0x38, 0xed]).is_none());
+    assert!(registry.decode(&[0xde, 0xad, 0xbe, 0xef, 0x00]).is_none());
+    assert!(registry
+        .decode(&calldata(
+            SWAP_EXACT_TOKENS_FOR_TOKENS,
+            &[Token::Bool(true)]
+        ))
+        .is_none());
+}
+
+#[test]
+fn identifies_and_decodes_hinted_transactions() {
+    let registry = SelectorRegistry::new();
+    let data = calldata(
+        [0x09, 0x5e, 0xa7, 0xb3],
+        &[Token::Address(Address::random()), Token::Uint(U256::MAX)],
+    );
+
+    let selector_only = hint(Some(SWAP_EXACT_TOKENS_FOR_TOKENS), None);
+    assert_eq!(
+        registry.transaction_function(&selector_only).unwrap().name,
+        "swapExactTokensForTokens"
+    );
+    assert!(registry.decode_transaction(&selector_only).is_none());
+
+    let with_calldata = hint(None, Some(data));
+    assert!(registry.transaction_function(&with_calldata).is_none());
+    assert_eq!(
+        registry.decode_transaction(&with_calldata).unwrap().name,
+        "approve"
+    );
+}
+
+#[test]
+fn extends_from_signatures_and_json_abi() {
+    let mut registry = SelectorRegistry::default();
+    assert!(registry.is_empty());
+
+    assert_eq!(
+        registry
+            .register_signatures(&[
+                "function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes data)"
+            ])
+            .unwrap(),
+        1
+    );
+    assert!(registry.register_signatures(&["function broken("]).is_err());
+
+    let abi = r#"[
+        {"type":"function","name":"deposit","inputs":[],"outputs":[],"stateMutability":"payable"},
+        {"type":"function","name":"withdraw","inputs":[{"name":"wad","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"},
+        {"type":"event","name":"Deposit","inputs":[{"name":"dst","type":"address","indexed":true},{"name":"wad","type":"uint256","indexed":false}],"anonymous":false}
+    ]"#;
+    let path = std::env::temp_dir().join(format!("weth-abi-{}.json", std::process::id()));
+    std::fs::write(&path, abi).unwrap();
+    let registered = registry.register_abi_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(registered.unwrap(), 2);
+    assert_eq!(registry.len(), 3);
+    let call = registry
+        .decode(&calldata(
+            [0x2e, 0x1a, 0x7d, 0x4d],
+            &[Token::Uint(U256::from(7))],
+        ))
+        .unwrap();
+    assert_eq!(call.param("wad"), Some(&Token::Uint(U256::from(7))));
+    assert!(registry.register_abi_file("/nonexistent/abi.json").is_err());
+}
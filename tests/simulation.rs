@@ -0,0 +1,83 @@
+use ethers::types::U256;
+use fixture::client;
+use mev_share_client::types::SimBundleOverrides;
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use spec::spec_bundle;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+#[tokio::test]
+async fn sim_bundle_sends_bundle_and_overrides() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({
+        "success": true,
+        "stateBlock": "0x8b8da8",
+        "mevGasPrice": "0x74c7906005",
+        "profit": "0x4bc800904fc000",
+        "refundableValue": "0x4bc800904fc000",
+        "gasUsed": "0xa620",
+        "logs": [{}, {}]
+    }))])
+    .await;
+
+    let response = client(&server)
+        .sim_bundle(
+            &spec_bundle("simple bundle"),
+            &SimBundleOverrides {
+                parent_block: Some(0x8b8da8.into()),
+                timeout: Some(5),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(response.success);
+    assert_eq!(response.profit, U256::from(0x4bc800904fc000u64));
+    assert_eq!(response.gas_used.as_u64(), 0xa620);
+    assert_eq!(response.logs.unwrap().len(), 2);
+
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "mev_simBundle");
+    assert_eq!(request["params"][0]["inclusion"]["block"], "0x1");
+    assert_eq!(
+        request["params"][1],
+        json!({ "parentBlock": "0x8b8da8", "timeout": 5 })
+    );
+}
+
+#[tokio::test]
+async fn sim_bundle_reports_failed_simulation() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({
+        "success": false,
+        "error": "tx failed",
+        "stateBlock": "0x1",
+        "mevGasPrice": "0x0",
+        "profit": "0x0",
+        "refundableValue": "0x0",
+        "gasUsed": "0x0"
+    }))])
+    .await;
+
+    let response = client(&server)
+        .sim_bundle(&spec_bundle("simple bundle"), &Default::default())
+        .await
+        .unwrap();
+
+    assert!(!response.success);
+    assert_eq!(response.error.as_deref(), Some("tx failed"));
+    assert_eq!(server.received()[0]["params"][1], json!({}));
+}
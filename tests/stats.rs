@@ -0,0 +1,103 @@
+use ethers::types::U256;
+use fixture::{client, BUNDLE_HASH};
+use mev_share_client::types::{BundleStats, UserStats};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+fn user_stats_response() -> serde_json::Value {
+    json!({
+        "isHighPriority": true,
+        "allTimeValidatorPayments": "1280749594841588639",
+        "allTimeGasSimulated": "30049470846",
+        "last7dValidatorPayments": "1280749594841588639",
+        "last7dGasSimulated": "30049470846",
+        "last1dValidatorPayments": "142305510537954293",
+        "last1dGasSimulated": "2731770076"
+    })
+}
+
+fn bundle_stats_response() -> serde_json::Value {
+    json!({
+        "isHighPriority": true,
+        "isSimulated": true,
+        "simulatedAt": "2022-10-06T21:36:06.317Z",
+        "receivedAt": "2022-10-06T21:36:06.250Z",
+        "consideredByBuildersAt": [
+            { "pubkey": "0x81babeec8c9f2bb9c329fd8a3b176032fe0ab5f3b92a3f44d4575a231c7bd9c31d10b6328ef68ed1e8c02a3dbc8e80f9", "timestamp": "2022-10-06T21:36:06.343Z" }
+        ],
+        "sealedByBuildersAt": [
+            { "pubkey": "0x81babeec8c9f2bb9c329fd8a3b176032fe0ab5f3b92a3f44d4575a231c7bd9c31d10b6328ef68ed1e8c02a3dbc8e80f9", "timestamp": "2022-10-06T21:36:07.742Z" }
+        ]
+    })
+}
+
+#[test]
+fn decodes_user_stats() {
+    let stats: UserStats = serde_json::from_value(user_stats_response()).unwrap();
+    assert!(stats.is_high_priority);
+    assert_eq!(
+        stats.all_time_validator_payments,
+        U256::from_dec_str("1280749594841588639").unwrap()
+    );
+    assert_eq!(stats.last_1d_gas_simulated, U256::from(2731770076u64));
+
+    let roundtrip = serde_json::to_value(&stats).unwrap();
+    assert_eq!(roundtrip, user_stats_response());
+}
+
+#[test]
+fn decodes_unsimulated_bundle_stats() {
+    let stats: BundleStats =
+        serde_json::from_value(json!({ "isSimulated": false, "isHighPriority": false })).unwrap();
+    assert!(!stats.is_simulated);
+    assert_eq!(stats.simulated_at, None);
+    assert!(stats.considered_by_builders_at.is_empty());
+}
+
+#[tokio::test]
+async fn gets_user_stats() {
+    let server = MockServer::spawn(vec![Reply::Result(user_stats_response())]).await;
+
+    let stats = client(&server)
+        .get_user_stats(17_000_000.into())
+        .await
+        .unwrap();
+
+    assert!(stats.is_high_priority);
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "flashbots_getUserStatsV2");
+    assert_eq!(request["params"], json!([{ "blockNumber": "0x1036640" }]));
+}
+
+#[tokio::test]
+async fn gets_bundle_stats_by_bundle_hash() {
+    let server = MockServer::spawn(vec![Reply::Result(bundle_stats_response())]).await;
+
+    let stats = client(&server)
+        .get_bundle_stats(BUNDLE_HASH.parse().unwrap(), 17_000_000.into())
+        .await
+        .unwrap();
+
+    assert!(stats.is_simulated);
+    assert_eq!(
+        stats.received_at.as_deref(),
+        Some("2022-10-06T21:36:06.250Z")
+    );
+    assert_eq!(stats.sealed_by_builders_at.len(), 1);
+    let request = &server.received()[0];
+    assert_eq!(request["method"], "flashbots_getBundleStatsV2");
+    assert_eq!(
+        request["params"],
+        json!([{ "bundleHash": BUNDLE_HASH, "blockNumber": "0x1036640" }])
+    );
+}
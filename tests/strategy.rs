@@ -0,0 +1,267 @@
+use anyhow::anyhow;
+use ethers::{
+    signers::LocalWallet,
+    types::{Address, H256},
+};
+use fixture::BUNDLE_HASH;
+use futures_util::{future::BoxFuture, stream, FutureExt, StreamExt};
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::MatchmakerConfig,
+    fixtures::SpecFixture,
+    strategy::{Classifier, Opportunity, PipelineReport, Strategy, StrategyPipeline},
+    types::{Bundle, PendingTransaction, PendingTxOrBundle, StreamingEventTypes},
+};
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use std::time::Duration;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const ROUTER: Address = Address::repeat_byte(0x11);
+
+async fn spawn_client(reply: Reply) -> (MatchmakerClient<LocalWallet>, MockServer) {
+    let server = MockServer::spawn(vec![reply]).await;
+    (fixture::client(&server), server)
+}
+
+async fn sending_client() -> (MatchmakerClient<LocalWallet>, MockServer) {
+    spawn_client(Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))).await
+}
+
+fn hint(to: Address) -> PendingTxOrBundle {
+    PendingTxOrBundle::Tx(PendingTransaction::new(
+        H256::random(),
+        None,
+        Some(to),
+        None,
+        None,
+        None,
+        None,
+    ))
+}
+
+fn hints(to: Address, count: usize) -> Vec<PendingTxOrBundle> {
+    (0..count).map(|_| hint(to)).collect()
+}
+
+/// Reports every transaction to the router, with the router as data
+struct RouterClassifier;
+
+impl Classifier for RouterClassifier {
+    fn classify(&self, hint: &PendingTxOrBundle) -> Option<Opportunity> {
+        match hint {
+            PendingTxOrBundle::Tx(tx) if tx.to == Some(ROUTER) => {
+                Some(Opportunity::new("router", hint.clone()).with_data(ROUTER))
+            }
+            _ => None,
+        }
+    }
+}
+
+enum Behavior {
+    Send,
+    Pass,
+    Fail,
+}
+
+struct TestStrategy {
+    name: &'static str,
+    kind: &'static str,
+    behavior: Behavior,
+    delay: Duration,
+}
+
+impl TestStrategy {
+    fn new(behavior: Behavior) -> Self {
+        Self {
+            name: "test",
+            kind: "router",
+            behavior,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+impl Strategy for TestStrategy {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn accepts(&self, opportunity: &Opportunity) -> bool {
+        opportunity.kind == self.kind
+    }
+
+    fn build<'a>(
+        &'a self,
+        opportunity: &'a Opportunity,
+    ) -> BoxFuture<'a, anyhow::Result<Option<Bundle>>> {
+        async move {
+            tokio::time::sleep(self.delay).await;
+            assert_eq!(opportunity.data::<Address>(), Some(&ROUTER));
+            match self.behavior {
+                Behavior::Send => Ok(Some(SpecFixture::spec().tests[0].bundle.clone())),
+                Behavior::Pass => Ok(None),
+                Behavior::Fail => Err(anyhow!("no route")),
+            }
+        }
+        .boxed()
+    }
+}
+
+#[tokio::test]
+async fn sends_bundles_for_classified_hints() {
+    let (client, server) = sending_client().await;
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(RouterClassifier)
+        .with_strategy(TestStrategy::new(Behavior::Send), 4);
+
+    let mut hints = hints(ROUTER, 2);
+    hints.push(hint(Address::random()));
+    let report = pipeline.run_hints(stream::iter(hints)).await;
+
+    assert_eq!(
+        report,
+        PipelineReport {
+            hints: 3,
+            opportunities: 2,
+            sent: 2,
+            ..Default::default()
+        }
+    );
+    let received = server.received();
+    assert_eq!(received.len(), 2);
+    assert!(received
+        .iter()
+        .all(|request| request["method"] == "mev_sendBundle"));
+}
+
+#[tokio::test]
+async fn passes_opportunities_to_accepting_strategies_only() {
+    let (client, server) = sending_client().await;
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(RouterClassifier)
+        .with_strategy(TestStrategy::new(Behavior::Pass), 4)
+        .with_strategy(
+            TestStrategy {
+                name: "other",
+                kind: "liquidation",
+                ..TestStrategy::new(Behavior::Send)
+            },
+            4,
+        );
+
+    let report = pipeline.run_hints(stream::iter(hints(ROUTER, 3))).await;
+
+    assert_eq!(report.opportunities, 3);
+    assert_eq!(report.passed, 3);
+    assert_eq!(report.sent, 0);
+    assert!(server.received().is_empty());
+}
+
+#[tokio::test]
+async fn skips_opportunities_over_the_concurrency_limit() {
+    let (client, _server) = sending_client().await;
+    let slow = || TestStrategy {
+        delay: Duration::from_millis(50),
+        ..TestStrategy::new(Behavior::Pass)
+    };
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(RouterClassifier)
+        .with_strategy(slow(), 1)
+        .with_strategy(
+            TestStrategy {
+                name: "wide",
+                ..slow()
+            },
+            3,
+        );
+
+    let report = pipeline.run_hints(stream::iter(hints(ROUTER, 3))).await;
+
+    assert_eq!(report.opportunities, 3);
+    assert_eq!(report.passed, 4);
+    assert_eq!(report.throttled, 2);
+}
+
+#[tokio::test]
+async fn releases_the_limit_when_a_strategy_finishes() {
+    let (client, _server) = sending_client().await;
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(RouterClassifier)
+        .with_strategy(
+            TestStrategy {
+                delay: Duration::from_millis(20),
+                ..TestStrategy::new(Behavior::Pass)
+            },
+            1,
+        );
+
+    let spaced = stream::iter(hints(ROUTER, 3)).then(|hint| async move {
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        hint
+    });
+    let report = pipeline.run_hints(spaced).await;
+
+    assert_eq!(report.passed, 3);
+    assert_eq!(report.throttled, 0);
+}
+
+#[tokio::test]
+async fn counts_failed_builds_and_submissions() {
+    let (client, _server) = spawn_client(Reply::Error(-32000)).await;
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(RouterClassifier)
+        .with_strategy(TestStrategy::new(Behavior::Fail), 4)
+        .with_strategy(
+            TestStrategy {
+                name: "sender",
+                ..TestStrategy::new(Behavior::Send)
+            },
+            4,
+        );
+
+    let report = pipeline.run_hints(stream::iter(hints(ROUTER, 2))).await;
+
+    assert_eq!(report.failed, 4);
+    assert_eq!(report.sent, 0);
+}
+
+#[tokio::test]
+async fn fails_to_run_without_an_event_stream() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let client = MatchmakerClient::from_config(&MatchmakerConfig {
+        stream_url: Some("http://127.0.0.1:1".to_string()),
+        ..fixture::config(&server)
+    })
+    .unwrap();
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(RouterClassifier)
+        .with_strategy(TestStrategy::new(Behavior::Send), 4);
+
+    assert!(pipeline
+        .run(StreamingEventTypes::Transaction)
+        .await
+        .is_err());
+    assert!(server.received().is_empty());
+}
+
+#[test]
+fn opportunity_data_is_typed() {
+    let opportunity = Opportunity::new("router", hint(ROUTER)).with_data(ROUTER);
+
+    assert_eq!(opportunity.data::<Address>(), Some(&ROUTER));
+    assert_eq!(opportunity.data::<u64>(), None);
+    assert_eq!(
+        Opportunity::new("router", hint(ROUTER)).data::<Address>(),
+        None
+    );
+}
@@ -0,0 +1,36 @@
+// Client fixture talking to a `MockServer`, included next to `mock_server.rs` by the tests that
+// send requests
+
+use super::mock_server::MockServer;
+use ethers::signers::LocalWallet;
+use mev_share_client::{
+    client::MatchmakerClient,
+    config::{AuthKeySource, MatchmakerConfig},
+};
+
+/// Auth key of the fixture clients, also used to sign transactions
+pub const KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+/// Bundle hash returned by mock servers accepting bundles
+pub const BUNDLE_HASH: &str = "0x2dc8b0bd8ab5a2c0b5b0b1e1e0eab8c1ee4ab2f06c5a1ab0d4d1c6a4f4d1e0b1";
+
+/// Config of a client sending all requests to `server`, for tests changing other fields
+pub fn config(server: &MockServer) -> MatchmakerConfig {
+    MatchmakerConfig {
+        network: "local".to_string(),
+        chain_id: Some(31337),
+        stream_url: Some(server.url.clone()),
+        api_url: Some(server.url.clone()),
+        auth_key: Some(AuthKeySource::Key(KEY.to_string())),
+        ..Default::default()
+    }
+}
+
+/// Client sending all requests to `server`
+pub fn client(server: &MockServer) -> MatchmakerClient<LocalWallet> {
+    MatchmakerClient::from_config(&config(server)).unwrap()
+}
+
+/// Wallet of [`KEY`]
+pub fn wallet() -> LocalWallet {
+    KEY.parse().unwrap()
+}
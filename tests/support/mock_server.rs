@@ -0,0 +1,159 @@
+// Minimal HTTP JSON-RPC server that answers requests with canned responses,
+// included by the tests that talk to a matchmaker
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::{collections::VecDeque, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+type Log<T> = Arc<Mutex<Vec<T>>>;
+
+/// A canned response, either the JSON-RPC `result` or an `error` object, or a plain JSON body
+pub enum Reply {
+    Result(Value),
+    Error(i64),
+    Status(u16),
+    Json(Value),
+}
+
+pub struct MockServer {
+    pub url: String,
+    /// Bodies of the requests received so far
+    pub requests: Log<Value>,
+    /// `X-Flashbots-Signature` headers of the requests received so far
+    pub signatures: Log<String>,
+    /// Request lines of the requests received so far, as sent, e.g. `GET /api/v1/history HTTP/1.1`
+    pub request_lines: Log<String>,
+}
+
+impl MockServer {
+    /// Serves `replies` in order, the last one is repeated once the others are used up
+    pub async fn spawn(replies: Vec<Reply>) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let signatures = Arc::new(Mutex::new(Vec::new()));
+        let request_lines = Arc::new(Mutex::new(Vec::new()));
+        let replies = Arc::new(Mutex::new(VecDeque::from(replies)));
+
+        let received = (requests.clone(), signatures.clone(), request_lines.clone());
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve(socket, received.clone(), replies.clone()));
+            }
+        });
+
+        MockServer {
+            url,
+            requests,
+            signatures,
+            request_lines,
+        }
+    }
+
+    pub fn received(&self) -> Vec<Value> {
+        self.requests.lock().clone()
+    }
+
+    pub fn received_signatures(&self) -> Vec<String> {
+        self.signatures.lock().clone()
+    }
+
+    pub fn received_request_lines(&self) -> Vec<String> {
+        self.request_lines.lock().clone()
+    }
+}
+
+async fn serve(
+    mut socket: TcpStream,
+    (requests, signatures, request_lines): (Log<Value>, Log<String>, Log<String>),
+    replies: Arc<Mutex<VecDeque<Reply>>>,
+) {
+    let mut buf = Vec::new();
+    loop {
+        let Some((head, body)) = read_request(&mut socket, &mut buf).await else {
+            return;
+        };
+        if let Some(signature) = header(&head, "x-flashbots-signature") {
+            signatures.lock().push(signature.to_string());
+        }
+        request_lines
+            .lock()
+            .push(head.lines().next().unwrap_or_default().to_string());
+        let request: Value = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&body).unwrap()
+        };
+        requests.lock().push(request.clone());
+
+        let (status, body) = {
+            let mut replies = replies.lock();
+            let reply = if replies.len() > 1 {
+                replies.pop_front().unwrap()
+            } else {
+                match replies.front().unwrap() {
+                    Reply::Result(result) => Reply::Result(result.clone()),
+                    Reply::Error(code) => Reply::Error(*code),
+                    Reply::Status(status) => Reply::Status(*status),
+                    Reply::Json(body) => Reply::Json(body.clone()),
+                }
+            };
+            match reply {
+                Reply::Result(result) => (
+                    200,
+                    json!({"jsonrpc": "2.0", "id": request["id"], "result": result}),
+                ),
+                Reply::Error(code) => (
+                    200,
+                    json!({"jsonrpc": "2.0", "id": request["id"], "error": {"code": code, "message": "mock error"}}),
+                ),
+                Reply::Status(status) => (status, Value::Null),
+                Reply::Json(body) => (200, body),
+            }
+        };
+
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 {status} Mock\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        if socket.write_all(response.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Returns the value of header `name`, given in lowercase. Header names are case-insensitive.
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// Reads the next request from the connection and returns its head, as sent, and its body
+async fn read_request(socket: &mut TcpStream, buf: &mut Vec<u8>) -> Option<(String, Vec<u8>)> {
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buf[..end]).to_string();
+            let len: usize = header(&head, "content-length")
+                .map(|len| len.parse().unwrap())
+                .unwrap_or(0);
+            if buf.len() >= end + 4 + len {
+                let body = buf[end + 4..end + 4 + len].to_vec();
+                buf.drain(..end + 4 + len);
+                return Some((head, body));
+            }
+        }
+
+        let mut chunk = [0u8; 4096];
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
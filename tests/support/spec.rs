@@ -0,0 +1,8 @@
+// Spec test vector helpers, included by the tests that need bundles of `tests.json`
+
+use mev_share_client::{fixtures::SpecFixture, types::Bundle};
+
+/// Bundle of the spec test vector called `name`
+pub fn spec_bundle(name: &str) -> Bundle {
+    SpecFixture::spec().test(name).unwrap().bundle.clone()
+}
@@ -0,0 +1,9 @@
+#![cfg(feature = "subscriber")]
+
+use mev_share_client::telemetry::init_subscriber;
+
+#[test]
+fn init_subscriber_does_not_panic_when_already_installed() {
+    let first = init_subscriber();
+    assert!(init_subscriber().is_err() || first.is_err());
+}
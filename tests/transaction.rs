@@ -0,0 +1,174 @@
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip2718::TypedTransaction, TransactionRequest, H256, U256},
+    utils::{
+        keccak256,
+        rlp::{Rlp, RlpStream},
+    },
+};
+use mev_share_client::{
+    transaction::{decode_signed_transaction, signed_transaction_hash, TxDecodeError},
+    types::{Bundle, BundleTx, MatchMakerNetwork},
+};
+use spec::spec_bundle;
+
+#[allow(dead_code)]
+mod spec {
+    include!("support/spec.rs");
+}
+
+const KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+#[test]
+fn decodes_eip1559_spec_tx() {
+    let decoded = spec_bundle("simple bundle").body[0]
+        .decode()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(decoded.tx_type, 2);
+    assert_eq!(
+        decoded.from,
+        "0xc87037874aed04e51c29f582394217a0a2b89d80"
+            .parse()
+            .unwrap()
+    );
+    assert_eq!(decoded.nonce, U256::zero());
+    assert_eq!(decoded.gas_limit, U256::from(21000));
+    assert_eq!(
+        decoded.max_priority_fee_per_gas,
+        Some(U256::from(1_000_000_000))
+    );
+    assert_eq!(decoded.tx.from(), Some(&decoded.from));
+}
+
+#[test]
+fn decodes_legacy_tx() {
+    let wallet: LocalWallet = KEY.parse::<LocalWallet>().unwrap().with_chain_id(1u64);
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(wallet.address())
+        .nonce(7)
+        .gas(21000)
+        .gas_price(30_000_000_000u64)
+        .chain_id(1)
+        .into();
+    let signature = wallet.sign_transaction_sync(&tx).unwrap();
+    let raw = tx.rlp_signed(&signature);
+
+    let decoded = decode_signed_transaction(&raw).unwrap();
+    assert_eq!(decoded.tx_type, 0);
+    assert_eq!(decoded.from, wallet.address());
+    assert_eq!(decoded.nonce, U256::from(7));
+    assert_eq!(decoded.max_fee_per_gas, U256::from(30_000_000_000u64));
+    assert_eq!(decoded.hash, H256::from(keccak256(&raw)));
+}
+
+/// Signs a blob transaction carrying `blob_hash`, returns its canonical encoding
+fn signed_blob_tx(wallet: &LocalWallet, blob_hash: H256) -> Vec<u8> {
+    let mut unsigned = RlpStream::new_list(11);
+    unsigned
+        .append(&1u64)
+        .append(&3u64)
+        .append(&U256::from(1))
+        .append(&U256::from(100))
+        .append(&U256::from(21000))
+        .append(&wallet.address())
+        .append(&U256::zero())
+        .append(&Vec::<u8>::new())
+        .begin_list(0);
+    unsigned.append(&U256::from(5)).append_list(&[blob_hash]);
+    let sighash = keccak256([&[3u8][..], unsigned.as_raw()].concat());
+    let signature = wallet.sign_hash(H256::from(sighash)).unwrap();
+
+    let mut signed = RlpStream::new_list(14);
+    for item in Rlp::new(unsigned.as_raw()).iter() {
+        signed.append_raw(item.as_raw(), 1);
+    }
+    signed
+        .append(&(signature.v - 27))
+        .append(&signature.r)
+        .append(&signature.s);
+    [&[3u8][..], signed.as_raw()].concat()
+}
+
+#[test]
+fn decodes_blob_tx() {
+    let wallet: LocalWallet = KEY.parse().unwrap();
+    let blob_hash = H256::repeat_byte(0x01);
+    let raw = signed_blob_tx(&wallet, blob_hash);
+
+    let decoded = decode_signed_transaction(&raw).unwrap();
+    assert_eq!(decoded.tx_type, 3);
+    assert_eq!(decoded.from, wallet.address());
+    assert_eq!(decoded.nonce, U256::from(3));
+    let blob = decoded.blob.unwrap();
+    assert_eq!(blob.max_fee_per_blob_gas, U256::from(5));
+    assert_eq!(blob.blob_versioned_hashes, vec![blob_hash]);
+}
+
+#[test]
+fn hashes_network_encoded_blob_tx_without_sidecar() {
+    let wallet: LocalWallet = KEY.parse().unwrap();
+    let raw = signed_blob_tx(&wallet, H256::repeat_byte(0x01));
+
+    let mut network = RlpStream::new_list(4);
+    network.append_raw(&raw[1..], 1);
+    for sidecar in [vec![0xaa; 32], vec![0xbb; 48], vec![0xcc; 48]] {
+        network.begin_list(1).append(&sidecar);
+    }
+    let network = [&[3u8][..], network.as_raw()].concat();
+
+    let hash = H256::from(keccak256(&raw));
+    assert_eq!(signed_transaction_hash(&network), hash);
+    assert_eq!(decode_signed_transaction(&network).unwrap().hash, hash);
+    let entry = BundleTx::Tx {
+        tx: network.into(),
+        can_revert: false,
+    };
+    assert_eq!(entry.hash(), hash);
+}
+
+#[test]
+fn decodes_nested_bundle_transactions() {
+    let decoded = spec_bundle("bundle with backrun of bundle")
+        .decode_transactions()
+        .unwrap();
+    assert_eq!(decoded.len(), 3);
+}
+
+#[test]
+fn rejects_unknown_tx_type() {
+    assert!(matches!(
+        decode_signed_transaction(&[0x05, 0xc0]),
+        Err(TxDecodeError::UnsupportedType(0x05))
+    ));
+}
+
+#[tokio::test]
+async fn signs_bundle_tx_for_network() {
+    let wallet: LocalWallet = KEY.parse().unwrap();
+    let network = MatchMakerNetwork {
+        chain_id: 5,
+        ..Default::default()
+    };
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(wallet.address())
+        .nonce(1)
+        .gas(21000)
+        .gas_price(1)
+        .into();
+
+    let mut bundle = Bundle::default();
+    bundle
+        .push_signed(&wallet, &network, tx, true)
+        .await
+        .unwrap();
+
+    let BundleTx::Tx { can_revert, .. } = &bundle.body[0] else {
+        panic!("expected a signed tx");
+    };
+    assert!(can_revert);
+    let decoded = bundle.body[0].decode().unwrap().unwrap();
+    assert_eq!(decoded.from, wallet.address());
+    assert_eq!(decoded.tx.chain_id(), Some(5.into()));
+}
@@ -0,0 +1,377 @@
+#![cfg(feature = "decoders")]
+
+use ethers::{
+    abi::{decode, encode, ParamType, Token},
+    signers::{LocalWallet, Signer},
+    types::{Address, Bytes, H256, U256, U64},
+    utils::id,
+};
+use fixture::BUNDLE_HASH;
+use futures_util::stream;
+use mev_share_client::{
+    nonce_manager::NonceManager,
+    strategy::{
+        uniswap_v2::{
+            get_amount_out, optimal_amount_in, BackrunConfig, HintedSync, UniswapV2Backrun,
+            UniswapV2Pair, UNISWAP_V2_SYNC,
+        },
+        Classifier, Opportunity, Strategy, StrategyPipeline,
+    },
+    types::{BundleTx, MatchMakerNetwork, PendingTransaction, PendingTxOrBundle},
+};
+use mev_share_rs::sse::EventTransactionLog;
+use mock_server::{MockServer, Reply};
+use serde_json::json;
+use std::sync::Arc;
+
+#[allow(dead_code)]
+mod mock_server {
+    include!("support/mock_server.rs");
+}
+
+#[allow(dead_code)]
+mod fixture {
+    include!("support/client.rs");
+}
+
+const V2_SYNC: &str = "0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1";
+
+const WETH: Address = Address::repeat_byte(0xee);
+const TOKEN: Address = Address::repeat_byte(0x70);
+const EXECUTOR: Address = Address::repeat_byte(0xec);
+/// Pair holding TOKEN as token0 and WETH as token1
+const PAIR_A: Address = Address::repeat_byte(0xaa);
+/// Pair holding WETH as token0 and TOKEN as token1
+const PAIR_B: Address = Address::repeat_byte(0xbb);
+
+fn ether(amount: u64) -> U256 {
+    U256::from(amount) * U256::exp10(18)
+}
+
+fn network() -> MatchMakerNetwork {
+    MatchMakerNetwork {
+        chain_id: 31337,
+        name: "local".to_string(),
+        stream_url: String::new(),
+        api_url: String::new(),
+    }
+}
+
+fn config() -> BackrunConfig {
+    BackrunConfig {
+        base_token: WETH,
+        executor: EXECUTOR,
+        refund_percent: 90,
+        min_profit: U256::zero(),
+        gas_limit: U256::from(300_000),
+        max_fee_per_gas: U256::from(50_000_000_000u64),
+        max_priority_fee_per_gas: U256::from(1_000_000_000),
+        blocks: 3,
+    }
+}
+
+/// Both pairs at a price of 2000 TOKEN per WETH
+fn strategy(config: BackrunConfig, network: MatchMakerNetwork) -> UniswapV2Backrun<LocalWallet> {
+    let signer = fixture::wallet();
+    let strategy = UniswapV2Backrun::new(config, signer, network, Arc::new(NonceManager::new()))
+        .with_pair(UniswapV2Pair::new(PAIR_A, TOKEN, WETH))
+        .with_pair(UniswapV2Pair::new(PAIR_B, WETH, TOKEN));
+    strategy.sync(PAIR_A, ether(200_000), ether(100));
+    strategy.sync(PAIR_B, ether(100), ether(200_000));
+    strategy
+}
+
+/// Reserves of pair A after a user bought TOKEN for 5 WETH
+fn hinted_swap() -> HintedSync {
+    let bought = get_amount_out(ether(5), ether(100), ether(200_000), 30);
+    HintedSync {
+        pair: PAIR_A,
+        reserve0: ether(200_000) - bought,
+        reserve1: ether(105),
+    }
+}
+
+fn sync_log(pair: Address, reserve0: U256, reserve1: U256) -> EventTransactionLog {
+    EventTransactionLog {
+        address: pair,
+        topics: vec![V2_SYNC.parse().unwrap()],
+        data: Bytes::from(encode(&[Token::Uint(reserve0), Token::Uint(reserve1)])),
+    }
+}
+
+fn hint(logs: Vec<EventTransactionLog>) -> PendingTxOrBundle {
+    PendingTxOrBundle::Tx(PendingTransaction::new(
+        H256::random(),
+        Some(logs),
+        None,
+        None,
+        None,
+        None,
+        None,
+    ))
+}
+
+fn swap_hint() -> PendingTxOrBundle {
+    let sync = hinted_swap();
+    hint(vec![sync_log(sync.pair, sync.reserve0, sync.reserve1)])
+}
+
+fn opportunity(syncs: Vec<HintedSync>) -> Opportunity {
+    Opportunity::new(UNISWAP_V2_SYNC, hint(Vec::new())).with_data(syncs)
+}
+
+fn cycle_profit(amount_in: U256, first: (U256, U256), second: (U256, U256)) -> U256 {
+    let bought = get_amount_out(amount_in, first.0, first.1, 30);
+    get_amount_out(bought, second.0, second.1, 30).saturating_sub(amount_in)
+}
+
+#[test]
+fn amount_out_matches_the_router() {
+    assert_eq!(
+        get_amount_out(ether(1), ether(100), ether(200_000), 30),
+        U256::from_dec_str("1974316068794122597700").unwrap()
+    );
+    assert_eq!(
+        get_amount_out(U256::zero(), ether(100), ether(200_000), 30),
+        U256::zero()
+    );
+    assert_eq!(
+        get_amount_out(ether(1), U256::zero(), U256::zero(), 30),
+        U256::zero()
+    );
+}
+
+#[test]
+fn amounts_too_large_to_compute_yield_nothing() {
+    assert_eq!(
+        get_amount_out(U256::MAX, 1.into(), U256::MAX, 30),
+        U256::zero()
+    );
+    assert_eq!(
+        get_amount_out(U256::MAX, U256::MAX, U256::MAX, 0),
+        U256::zero()
+    );
+    assert_eq!(
+        optimal_amount_in((1.into(), U256::MAX), 0, (1.into(), U256::MAX), 0),
+        None
+    );
+}
+
+#[test]
+fn optimal_amount_maximizes_profit() {
+    let sync = hinted_swap();
+    // buy TOKEN in pair B, sell it to pair A
+    let first = (ether(100), ether(200_000));
+    let second = (sync.reserve0, sync.reserve1);
+
+    let amount = optimal_amount_in(first, 30, second, 30).unwrap();
+    let profit = cycle_profit(amount, first, second);
+
+    assert!(!profit.is_zero());
+    for other in [
+        amount * 99 / 100,
+        amount * 101 / 100,
+        amount / 2,
+        amount * 2,
+    ] {
+        assert!(cycle_profit(other, first, second) <= profit);
+    }
+}
+
+#[test]
+fn no_optimal_amount_without_price_difference() {
+    let reserves = (ether(100), ether(200_000));
+    let reversed = (ether(200_000), ether(100));
+
+    assert_eq!(optimal_amount_in(reserves, 30, reversed, 30), None);
+    // a price difference smaller than the fees is not profitable either
+    let shifted = (ether(199_500), ether(100));
+    assert_eq!(optimal_amount_in(reserves, 30, shifted, 30), None);
+}
+
+#[test]
+fn classifies_syncs_of_configured_pairs() {
+    let classifier = strategy(config(), network()).classifier();
+    let sync = hinted_swap();
+
+    let opportunity = classifier.classify(&swap_hint()).unwrap();
+    assert_eq!(opportunity.kind, UNISWAP_V2_SYNC);
+    assert_eq!(opportunity.data::<Vec<HintedSync>>(), Some(&vec![sync]));
+
+    let other_pair = hint(vec![sync_log(Address::random(), ether(1), ether(1))]);
+    assert!(classifier.classify(&other_pair).is_none());
+
+    let topics_only = hint(vec![EventTransactionLog {
+        data: Bytes::new(),
+        ..sync_log(PAIR_A, ether(1), ether(1))
+    }]);
+    assert!(classifier.classify(&topics_only).is_none());
+}
+
+#[test]
+fn finds_arbitrage_against_the_hinted_swap() {
+    let strategy = strategy(config(), network());
+    let sync = hinted_swap();
+
+    let arbitrage = strategy.find_arbitrage(&[sync]).unwrap();
+
+    assert_eq!(arbitrage.first_pair, PAIR_B);
+    assert_eq!(arbitrage.second_pair, PAIR_A);
+    let first = (ether(100), ether(200_000));
+    let second = (sync.reserve0, sync.reserve1);
+    assert_eq!(
+        Some(arbitrage.amount_in),
+        optimal_amount_in(first, 30, second, 30)
+    );
+    assert_eq!(
+        arbitrage.profit,
+        cycle_profit(arbitrage.amount_in, first, second)
+    );
+    // hinted reserves are not confirmed
+    assert_eq!(
+        strategy.reserves(PAIR_A),
+        Some((ether(200_000), ether(100)))
+    );
+}
+
+#[test]
+fn ignores_unknown_pairs_and_unsynced_reserves() {
+    let strategy = strategy(config(), network());
+    let unknown = HintedSync {
+        pair: Address::random(),
+        ..hinted_swap()
+    };
+    assert_eq!(strategy.find_arbitrage(&[unknown]), None);
+
+    let signer = fixture::wallet();
+    let unsynced = UniswapV2Backrun::new(config(), signer, network(), Default::default())
+        .with_pair(UniswapV2Pair::new(PAIR_A, TOKEN, WETH))
+        .with_pair(UniswapV2Pair::new(PAIR_B, WETH, TOKEN));
+    assert_eq!(unsynced.find_arbitrage(&[hinted_swap()]), None);
+
+    unsynced.sync(Address::random(), ether(1), ether(1));
+    assert_eq!(unsynced.reserves(PAIR_A), None);
+}
+
+#[tokio::test]
+async fn builds_backrun_with_refund() {
+    let strategy = strategy(config(), network());
+    strategy.on_block(U64::from(100));
+    let opportunity = opportunity(vec![hinted_swap()]);
+    let arbitrage = strategy.find_arbitrage(&[hinted_swap()]).unwrap();
+
+    let bundle = strategy.build(&opportunity).await.unwrap().unwrap();
+
+    assert_eq!(bundle.inclusion.block, U64::from(101));
+    assert_eq!(bundle.inclusion.max_block, Some(U64::from(103)));
+    assert!(matches!(bundle.body[0], BundleTx::TxHash { hash } if hash == opportunity.hint.hash()));
+    let refund = &bundle.validity.as_ref().unwrap().refund.as_ref().unwrap()[0];
+    assert_eq!((refund.body_idx, refund.percent), (0, 90));
+    bundle.validate().unwrap();
+
+    let backrun = bundle.body[1].decode().unwrap().unwrap();
+    let signer = fixture::wallet();
+    assert_eq!(backrun.from, signer.address());
+    assert_eq!(backrun.nonce, U256::zero());
+    assert_eq!(backrun.gas_limit, U256::from(300_000));
+    assert_eq!(backrun.tx.to_addr(), Some(&EXECUTOR));
+
+    let data = backrun.tx.data().unwrap();
+    assert_eq!(
+        data[..4],
+        id("backrun(address,address,address,uint256,uint256)")
+    );
+    let params = decode(
+        &[
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+        ],
+        &data[4..],
+    )
+    .unwrap();
+    assert_eq!(
+        params,
+        vec![
+            Token::Address(PAIR_B),
+            Token::Address(PAIR_A),
+            Token::Address(WETH),
+            Token::Uint(arbitrage.amount_in),
+            Token::Uint(arbitrage.amount_in),
+        ]
+    );
+
+    let next = strategy.build(&opportunity).await.unwrap().unwrap();
+    assert_eq!(next.body[1].decode().unwrap().unwrap().nonce, U256::one());
+}
+
+#[tokio::test]
+async fn skips_unprofitable_opportunities() {
+    let profit = strategy(config(), network())
+        .find_arbitrage(&[hinted_swap()])
+        .unwrap()
+        .profit;
+    let strategy = strategy(
+        BackrunConfig {
+            min_profit: profit,
+            ..config()
+        },
+        network(),
+    );
+    strategy.on_block(U64::from(100));
+
+    assert!(strategy
+        .build(&opportunity(vec![hinted_swap()]))
+        .await
+        .unwrap()
+        .is_none());
+    let unchanged = HintedSync {
+        pair: PAIR_A,
+        reserve0: ether(200_000),
+        reserve1: ether(100),
+    };
+    assert!(strategy
+        .build(&opportunity(vec![unchanged]))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn requires_the_latest_block() {
+    let strategy = strategy(config(), network());
+
+    assert!(strategy
+        .build(&opportunity(vec![hinted_swap()]))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn sends_backruns_through_the_pipeline() {
+    let server = MockServer::spawn(vec![Reply::Result(json!({ "bundleHash": BUNDLE_HASH }))]).await;
+    let client = fixture::client(&server);
+    let strategy = strategy(config(), client.network().clone());
+    strategy.on_block(U64::from(100));
+    let pipeline = StrategyPipeline::new(client)
+        .with_classifier(strategy.classifier())
+        .with_strategy(strategy, 1);
+
+    let unrelated = hint(vec![sync_log(Address::random(), ether(1), ether(1))]);
+    let report = pipeline
+        .run_hints(stream::iter(vec![swap_hint(), unrelated]))
+        .await;
+
+    assert_eq!(report.hints, 2);
+    assert_eq!(report.opportunities, 1);
+    assert_eq!(report.sent, 1);
+    let received = server.received();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0]["method"], "mev_sendBundle");
+    assert_eq!(
+        received[0]["params"][0]["validity"]["refund"][0]["percent"],
+        90
+    );
+}
@@ -0,0 +1,137 @@
+use mev_share_client::fixtures::SpecFixture;
+use mev_share_client::types::{
+    Bundle, BundleTx, InclusionParams, Refund, RefundConfig, ValidityParams,
+};
+use mev_share_client::validation::BundleValidationError;
+use serde_json::Value;
+
+fn spec_bundles() -> Vec<Bundle> {
+    SpecFixture::spec()
+        .tests
+        .into_iter()
+        .map(|test| test.bundle)
+        .collect()
+}
+
+fn backrun_bundle() -> Bundle {
+    spec_bundles()
+        .into_iter()
+        .find(|bundle| {
+            bundle
+                .validity
+                .as_ref()
+                .and_then(|v| v.refund.as_ref())
+                .is_some()
+        })
+        .unwrap()
+}
+
+#[test]
+fn spec_bundles_are_valid() {
+    for bundle in spec_bundles() {
+        assert_eq!(bundle.validate(), Ok(()));
+    }
+}
+
+#[test]
+fn spec_bundles_serialize_to_the_spec_shape() {
+    let spec: Value = serde_json::from_str(include_str!("tests.json")).unwrap();
+    for test in spec["tests"].as_array().unwrap() {
+        let bundle: Bundle = serde_json::from_value(test["bundle"].clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&bundle).unwrap(),
+            test["bundle"],
+            "{}",
+            test["name"]
+        );
+    }
+}
+
+#[test]
+fn rejects_empty_body() {
+    let mut bundle = backrun_bundle();
+    bundle.body.clear();
+    assert_eq!(bundle.validate(), Err(BundleValidationError::EmptyBody));
+}
+
+#[test]
+fn rejects_refund_out_of_range() {
+    let mut bundle = backrun_bundle();
+    bundle.validity = Some(ValidityParams {
+        refund: Some(vec![Refund {
+            body_idx: 2,
+            percent: 90,
+        }]),
+        refund_config: None,
+    });
+    assert_eq!(
+        bundle.validate(),
+        Err(BundleValidationError::RefundIndexOutOfRange {
+            body_idx: 2,
+            body_len: 2
+        })
+    );
+}
+
+#[test]
+fn rejects_refund_percent_over_100() {
+    let mut bundle = backrun_bundle();
+    bundle.validity = Some(ValidityParams {
+        refund: Some(vec![Refund {
+            body_idx: 0,
+            percent: 101,
+        }]),
+        refund_config: None,
+    });
+    assert_eq!(
+        bundle.validate(),
+        Err(BundleValidationError::RefundPercentTooHigh { percent: 101 })
+    );
+}
+
+#[test]
+fn rejects_refund_config_not_summing_to_100() {
+    let mut bundle = backrun_bundle();
+    bundle.validity = Some(ValidityParams {
+        refund: None,
+        refund_config: Some(vec![RefundConfig {
+            address: "0xc87037874aed04e51c29f582394217a0a2b89d80".to_string(),
+            percent: 50,
+        }]),
+    });
+    assert_eq!(
+        bundle.validate(),
+        Err(BundleValidationError::RefundConfigPercentSum { total: 50 })
+    );
+}
+
+#[test]
+fn rejects_max_block_before_block() {
+    let mut bundle = backrun_bundle();
+    bundle.inclusion = InclusionParams {
+        block: 10.into(),
+        max_block: Some(9.into()),
+    };
+    assert_eq!(
+        bundle.validate(),
+        Err(BundleValidationError::MaxBlockBeforeBlock {
+            block: 10.into(),
+            max_block: 9.into()
+        })
+    );
+}
+
+#[test]
+fn rejects_nesting_too_deep() {
+    let mut bundle = backrun_bundle();
+    for _ in 0..2 {
+        let nested = bundle.clone();
+        bundle.body[0] = BundleTx::Bundle {
+            bundle: Box::new(nested),
+        };
+    }
+    assert_eq!(
+        bundle.validate(),
+        Err(BundleValidationError::NestingTooDeep { depth: 2 })
+    );
+}